@@ -9,43 +9,188 @@
 /// Repository:
 /// =====================================================================
 ///
-use clap::{ArgAction, Parser};
+use chrono::NaiveDateTime;
+use clap::{ArgAction, Parser, Subcommand};
+use std::path::PathBuf;
+
+use crate::restore::{self, ConflictPolicy, VersionSelect};
 
 #[derive(Parser)]
-#[command(arg_required_else_help = false)]
+#[command(
+    arg_required_else_help = false,
+    disable_version_flag = true,
+    subcommand_value_name = "COMMAND",
+    after_help = "Without a command, rrm trashes FILES the way rm would. To trash a file \
+        named like a command, write it as a path or after --, i.e. `rrm ./list` or `rrm -- list`."
+)]
 #[command(
     version = "0.3.0",
     author = "Robert Pellegrin",
     about = "A modern take on the traditional rm utility, written in Rust.
         \nAuthor: Robert Pellegrin"
 )]
-
 pub struct Args {
-    /// remove directories and their contents recursively
-    #[arg(short = 'r', long, action = ArgAction::SetTrue)]
-    pub recursive: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub rm: RmOptions,
 
     /// list contents of trash directory
     #[arg(long, action = ArgAction::SetTrue)]
     pub view_trash: bool,
 
     /// explain what is being done.
-    #[arg(short = 'v', long, action = ArgAction::SetTrue)]
+    #[arg(short = 'v', long, global = true, action = ArgAction::SetTrue)]
     pub verbose: bool,
 
-    /// prompt before every removal
-    #[arg(short = 'i', action = ArgAction::SetTrue)]
-    pub interactive: bool,
-
     /// permanently delete all files in the trash directory
     #[arg(long, action = ArgAction::SetTrue)]
     pub empty: bool,
 
-    /// delete files/directories without moving to trash bin
-    #[arg(short = 's', long, action = ArgAction::SetTrue)]
-    pub skip_trash: bool,
+    /// restore the named trash entries to their original location
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub restore: bool,
+
+    /// show how a trashed file differs from the file now at its original path
+    #[arg(long, value_name = "ITEM", conflicts_with = "restore")]
+    pub diff: Option<String>,
+
+    #[command(flatten)]
+    pub restore_options: RestoreOptions,
 
     /// list of files/directories to send to trash
     #[arg()]
     pub files: Vec<String>,
 }
+
+/// Options shared by rm-compatible mode and `rrm put`.
+#[derive(clap::Args)]
+pub struct RmOptions {
+    /// remove directories and their contents recursively
+    #[arg(short = 'r', long, action = ArgAction::SetTrue)]
+    pub recursive: bool,
+
+    /// prompt before every removal
+    #[arg(short = 'i', action = ArgAction::SetTrue)]
+    pub interactive: bool,
+
+    /// delete files/directories without moving to trash bin
+    #[arg(short = 's', long, action = ArgAction::SetTrue)]
+    pub skip_trash: bool,
+}
+
+/// Options shared by --restore and `rrm restore`.
+#[derive(clap::Args)]
+pub struct RestoreOptions {
+    /// restore into DIR instead of the original location
+    #[arg(
+        long,
+        visible_alias = "restore-to",
+        value_name = "DIR",
+        conflicts_with = "here"
+    )]
+    pub to: Option<PathBuf>,
+
+    /// restore into the current directory
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub here: bool,
+
+    /// restore the most recently deleted version of a path
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with = "version")]
+    pub latest: bool,
+
+    /// print version, or with --restore or --diff pick the Nth deleted version of a path
+    #[arg(short = 'V', long, value_name = "N", num_args = 0..=1, default_missing_value = "0")]
+    pub version: Option<usize>,
+
+    /// restore everything deleted since TIME (HH:MM, YYYY-MM-DD or both)
+    #[arg(
+        long,
+        visible_alias = "since",
+        value_name = "TIME",
+        value_parser = restore::parse_since,
+        conflicts_with_all = ["to", "here"]
+    )]
+    pub restore_since: Option<NaiveDateTime>,
+
+    /// with --restore-since, only restore items deleted from below DIR
+    #[arg(long, value_name = "DIR", requires = "restore_since")]
+    pub under: Option<PathBuf>,
+
+    /// show what would be restored without restoring anything
+    #[arg(short = 'n', long, action = ArgAction::SetTrue)]
+    pub dry_run: bool,
+
+    /// what to do when a restored file's original path already exists
+    #[arg(long, value_enum, default_value_t = ConflictPolicy::Ask)]
+    pub on_conflict: ConflictPolicy,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// move files/directories to the trash
+    Put {
+        #[command(flatten)]
+        rm: RmOptions,
+
+        /// list of files/directories to send to trash
+        #[arg(required = true)]
+        files: Vec<String>,
+    },
+
+    /// print the contents of the trash
+    List,
+
+    /// restore trash entries, by name or by the path they were deleted from
+    Restore {
+        #[command(flatten)]
+        options: RestoreOptions,
+
+        /// trash entries or original paths to restore
+        #[arg(required_unless_present = "restore_since")]
+        items: Vec<String>,
+    },
+
+    /// permanently delete entries from the trash
+    Purge {
+        /// do not ask for confirmation
+        #[arg(short = 'f', long, action = ArgAction::SetTrue)]
+        force: bool,
+
+        /// trash entries or original paths to delete
+        #[arg(required = true)]
+        items: Vec<String>,
+    },
+
+    /// permanently delete all files in the trash directory
+    Empty,
+
+    /// browse the trash interactively
+    View {
+        /// what to do when a restored file's original path already exists
+        #[arg(long, value_enum, default_value_t = ConflictPolicy::Ask)]
+        on_conflict: ConflictPolicy,
+    },
+
+    /// print a summary of the trash
+    Stats,
+}
+
+impl Args {
+    /// True if the files should be restored rather than trashed.
+    pub fn restoring(&self) -> bool {
+        self.restore || self.restore_options.to.is_some() || self.restore_options.here
+    }
+}
+
+impl RestoreOptions {
+    /// How to choose between several trashed versions of the same path.
+    pub fn version_select(&self) -> VersionSelect {
+        match self.version {
+            Some(n) if n > 0 => VersionSelect::Nth(n),
+            _ if self.latest => VersionSelect::Latest,
+            _ => VersionSelect::Ask,
+        }
+    }
+}
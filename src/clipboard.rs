@@ -0,0 +1,46 @@
+///
+/// =====================================================================
+/// Project Name: rust rm
+/// Description: An enhanced version of the common rm utility.
+/// Author: Robert Pellegrin
+/// Date: 2025-06-29
+/// Version: 0.0.1
+/// License: MIT
+/// Repository:
+/// =====================================================================
+///
+/// Copies text to the clipboard with the OSC 52 escape sequence. The
+/// terminal does the copying, so it works over SSH and without a clipboard
+/// daemon. Inside tmux it needs `set -g set-clipboard on`.
+///
+use std::io::{self, Write};
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Asks the terminal to put `text` on the system clipboard.
+pub fn copy(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stdout.flush()
+}
+
+/// Standard base64 with padding, as OSC 52 expects.
+fn base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = u32::from(b[0]) << 16 | u32::from(b[1]) << 8 | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
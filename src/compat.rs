@@ -0,0 +1,428 @@
+///
+/// =====================================================================
+/// Project Name: rust rm
+/// Description: An enhanced version of the common rm utility.
+/// Author: Robert Pellegrin
+/// Date: 2025-06-12
+/// Version: 0.0.1
+/// License: MIT
+/// Repository:
+/// =====================================================================
+///
+/// Lets rrm stand in for trash-cli. When the binary is invoked through a
+/// symlink named trash-put, trash-list, trash-restore, trash-empty or
+/// trash-rm, it parses that tool's flags and mimics its output.
+///
+use chrono::{Local, TimeDelta};
+use clap::{ArgAction, Parser};
+use std::{
+    env,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    empty, mv,
+    restore::{self, ConflictPolicy},
+    trash::{self, TrashItem},
+};
+
+/// trash-cli commands that rrm can stand in for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tool {
+    Put,
+    List,
+    Restore,
+    Empty,
+    Rm,
+}
+
+/// Returns the trash-cli command rrm was invoked as, if any.
+pub fn invoked_as() -> Option<Tool> {
+    let program = PathBuf::from(env::args_os().next()?);
+    match program.file_name()?.to_str()? {
+        "trash-put" | "trash" => Some(Tool::Put),
+        "trash-list" => Some(Tool::List),
+        "trash-restore" => Some(Tool::Restore),
+        "trash-empty" => Some(Tool::Empty),
+        "trash-rm" => Some(Tool::Rm),
+        _ => None,
+    }
+}
+
+/// Runs `tool` with the process arguments. Returns the exit code.
+pub fn run(tool: Tool) -> i32 {
+    let result = match tool {
+        Tool::Put => trash_put(PutArgs::parse()),
+        Tool::List => trash_list(ListArgs::parse()),
+        Tool::Restore => trash_restore(RestoreArgs::parse()),
+        Tool::Empty => trash_empty(EmptyArgs::parse()),
+        Tool::Rm => trash_rm(RmArgs::parse()),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+#[derive(Parser)]
+#[command(name = "trash-put", version, about = "Put files in trash")]
+struct PutArgs {
+    /// ignored (for GNU rm compatibility)
+    #[arg(short = 'd', long, action = ArgAction::SetTrue)]
+    directory: bool,
+
+    /// silently ignore nonexistent files
+    #[arg(short = 'f', long, action = ArgAction::SetTrue)]
+    force: bool,
+
+    /// prompt before every removal
+    #[arg(short = 'i', long, action = ArgAction::SetTrue)]
+    interactive: bool,
+
+    /// ignored (for GNU rm compatibility)
+    #[arg(short = 'r', short_alias = 'R', long, action = ArgAction::SetTrue)]
+    recursive: bool,
+
+    /// explain what is being done
+    #[arg(short = 'v', long, action = ArgAction::SetTrue)]
+    verbose: bool,
+
+    #[arg(required = true)]
+    files: Vec<String>,
+}
+
+#[derive(Parser)]
+#[command(name = "trash-list", version, about = "List trashed files")]
+struct ListArgs {}
+
+#[derive(Parser)]
+#[command(
+    name = "trash-restore",
+    version,
+    about = "Restore for Command Line trash"
+)]
+struct RestoreArgs {
+    /// sort list of restore candidates by parameter
+    #[arg(long, value_parser = ["date", "path", "none"], default_value = "date")]
+    sort: String,
+
+    /// overwrite existing files with files coming out of the trash
+    #[arg(long, action = ArgAction::SetTrue)]
+    overwrite: bool,
+
+    /// only offer files deleted from below this path
+    #[arg()]
+    path: Option<PathBuf>,
+}
+
+#[derive(Parser)]
+#[command(name = "trash-empty", version, about = "Purge trashed files")]
+struct EmptyArgs {
+    /// ask before emptying the trash
+    #[arg(short = 'i', long, action = ArgAction::SetTrue)]
+    interactive: bool,
+
+    /// don't ask before emptying the trash
+    #[arg(short = 'f', action = ArgAction::SetTrue)]
+    force: bool,
+
+    /// only delete files trashed more than DAYS days ago
+    #[arg()]
+    days: Option<u32>,
+}
+
+#[derive(Parser)]
+#[command(
+    name = "trash-rm",
+    version,
+    about = "Remove trashed files matching a pattern"
+)]
+struct RmArgs {
+    /// file name pattern, or an absolute path pattern if it starts with /
+    #[arg()]
+    pattern: String,
+}
+
+fn trash_put(args: PutArgs) -> io::Result<i32> {
+    let mut code = 0;
+    for file in &args.files {
+        if args.force && !trash::path_exists(Path::new(file)) {
+            continue;
+        }
+        if args.interactive && !mv::confirm(&format!("trash-put: trash '{}'?", file))? {
+            continue;
+        }
+        // Unlike rrm, trash-put moves symlinks to the trash too.
+        if let Err(e) = mv::trash(Path::new(file)) {
+            eprintln!("trash-put: cannot trash '{}': {}", file, e);
+            code = 1;
+        } else if args.verbose {
+            eprintln!(
+                "trash-put: '{}' trashed in {}",
+                file,
+                trash::trash_dir()?.display()
+            );
+        }
+    }
+    Ok(code)
+}
+
+/// Formats a deletion date the way trash-cli does, i.e. 2025-05-17 12:00:00
+fn display_date(item: &TrashItem) -> String {
+    item.deleted_at()
+        .map(|date| date.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| item.deletion_date.replace('T', " "))
+}
+
+fn trash_list(_args: ListArgs) -> io::Result<i32> {
+    for item in trash::list_items()? {
+        println!("{} {}", display_date(&item), item.original_path.display());
+    }
+    Ok(0)
+}
+
+fn trash_restore(args: RestoreArgs) -> io::Result<i32> {
+    let under = match &args.path {
+        Some(path) => trash::absolute_path(path)?,
+        None => env::current_dir()?,
+    };
+
+    let mut items: Vec<TrashItem> = trash::list_items()?
+        .into_iter()
+        .filter(|item| item.original_path.starts_with(&under))
+        .collect();
+    match args.sort.as_str() {
+        "date" => items.sort_by_key(TrashItem::deleted_at),
+        "path" => items.sort_by(|a, b| a.original_path.cmp(&b.original_path)),
+        _ => {}
+    }
+
+    if items.is_empty() {
+        println!("No files trashed from current dir ('{}')", under.display());
+        return Ok(0);
+    }
+
+    for (i, item) in items.iter().enumerate() {
+        println!(
+            "{:4} {} {}",
+            i,
+            display_date(item),
+            item.original_path.display()
+        );
+    }
+
+    print!("What file to restore [0..{}]: ", items.len() - 1);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    if input.trim().is_empty() {
+        println!("Exiting");
+        return Ok(0);
+    }
+
+    let Some(indexes) = parse_ranges(&input, items.len()) else {
+        eprintln!("Invalid entry: not an index: {}", input.trim());
+        return Ok(1);
+    };
+
+    let policy = if args.overwrite {
+        ConflictPolicy::Overwrite
+    } else {
+        ConflictPolicy::Skip
+    };
+
+    let mut code = 0;
+    for i in indexes {
+        let item = &items[i];
+        match restore::restore_item(item, policy) {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                eprintln!(
+                    "Refusing to overwrite existing file \"{}\".",
+                    item.original_path.display()
+                );
+                code = 1;
+            }
+            Err(e) => {
+                eprintln!("{}: {}", item.original_path.display(), e);
+                code = 1;
+            }
+        }
+    }
+    Ok(code)
+}
+
+/// Parses a trash-restore selection such as "0", "0-2" or "0-2, 4" into
+/// indexes below `count`. Returns None if any part is invalid.
+fn parse_ranges(input: &str, count: usize) -> Option<Vec<usize>> {
+    let mut indexes = Vec::new();
+    for part in input
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+    {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (start.trim().parse().ok()?, end.trim().parse().ok()?),
+            None => {
+                let index = part.parse().ok()?;
+                (index, index)
+            }
+        };
+        if start > end || end >= count {
+            return None;
+        }
+        for index in start..=end {
+            if !indexes.contains(&index) {
+                indexes.push(index);
+            }
+        }
+    }
+    Some(indexes)
+}
+
+fn trash_empty(args: EmptyArgs) -> io::Result<i32> {
+    if args.interactive && !args.force {
+        let prompt = format!("Would empty {}, continue?", trash::trash_dir()?.display());
+        if !mv::confirm(&prompt)? {
+            return Ok(0);
+        }
+    }
+
+    let Some(days) = args.days else {
+        empty::delete_all()?;
+        return Ok(0);
+    };
+
+    let Some(cutoff) = TimeDelta::try_days(i64::from(days))
+        .and_then(|age| Local::now().naive_local().checked_sub_signed(age))
+    else {
+        eprintln!("trash-empty: {} days is too far back", days);
+        return Ok(1);
+    };
+    for item in trash::list_items()? {
+        if item.deleted_at().is_some_and(|date| date < cutoff) {
+            empty::purge_item(&item)?;
+        }
+    }
+    Ok(0)
+}
+
+fn trash_rm(args: RmArgs) -> io::Result<i32> {
+    for item in trash::list_items()? {
+        // Patterns starting with / match the whole original path, anything
+        // else only its file name.
+        let subject = if args.pattern.starts_with('/') {
+            item.original_path.to_string_lossy().into_owned()
+        } else {
+            item.original_name().to_string_lossy().into_owned()
+        };
+
+        if glob_match(&args.pattern, &subject) {
+            empty::purge_item(&item)?;
+        }
+    }
+    Ok(0)
+}
+
+/// Matches `text` against a shell-style pattern supporting *, ? and [...].
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    glob_match_from(&pattern, &text)
+}
+
+fn glob_match_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') => (0..=text.len()).any(|skip| glob_match_from(&pattern[1..], &text[skip..])),
+        Some('?') => !text.is_empty() && glob_match_from(&pattern[1..], &text[1..]),
+        Some('[') => {
+            let Some(close) = pattern
+                .iter()
+                .skip(2)
+                .position(|&c| c == ']')
+                .map(|i| i + 2)
+            else {
+                return text.first() == Some(&'[') && glob_match_from(&pattern[1..], &text[1..]);
+            };
+            let Some(&c) = text.first() else {
+                return false;
+            };
+
+            let class = &pattern[1..close];
+            let (negated, class) = match class.first() {
+                Some('!') | Some('^') => (true, &class[1..]),
+                _ => (false, class),
+            };
+
+            let mut matched = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == '-' {
+                    matched |= class[i] <= c && c <= class[i + 2];
+                    i += 3;
+                } else {
+                    matched |= class[i] == c;
+                    i += 1;
+                }
+            }
+
+            matched != negated && glob_match_from(&pattern[close + 1..], &text[1..])
+        }
+        Some(&p) => text.first() == Some(&p) && glob_match_from(&pattern[1..], &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_match_wildcards() {
+        assert!(glob_match("*.log", "build.log"));
+        assert!(glob_match("*.log", ".log"));
+        assert!(!glob_match("*.log", "build.log.1"));
+        assert!(glob_match("a?c", "abc"));
+        assert!(!glob_match("a?c", "ac"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("/home/*/notes*", "/home/me/notes.txt"));
+        assert!(!glob_match("notes", "notes.txt"));
+    }
+
+    #[test]
+    fn glob_match_classes() {
+        assert!(glob_match("file[0-9].txt", "file7.txt"));
+        assert!(!glob_match("file[0-9].txt", "filex.txt"));
+        assert!(glob_match("file[!0-9].txt", "filex.txt"));
+        assert!(glob_match("file[^ab]", "filec"));
+        assert!(glob_match("[ab-]", "-"));
+        assert!(glob_match("[]]", "]"));
+        // An unclosed [ is a literal
+        assert!(glob_match("a[b", "a[b"));
+        assert!(!glob_match("[a]", ""));
+    }
+
+    #[test]
+    fn parse_ranges_accepts_lists_and_ranges() {
+        assert_eq!(parse_ranges("0", 3), Some(vec![0]));
+        assert_eq!(parse_ranges("0-2", 3), Some(vec![0, 1, 2]));
+        assert_eq!(parse_ranges(" 2 , 0 - 1 ", 3), Some(vec![2, 0, 1]));
+        assert_eq!(parse_ranges("1, 0-2", 3), Some(vec![1, 0, 2]));
+        assert_eq!(parse_ranges("", 3), Some(vec![]));
+    }
+
+    #[test]
+    fn parse_ranges_rejects_invalid_parts() {
+        assert_eq!(parse_ranges("3", 3), None);
+        assert_eq!(parse_ranges("2-1", 3), None);
+        assert_eq!(parse_ranges("0-5", 3), None);
+        assert_eq!(parse_ranges("-1", 3), None);
+        assert_eq!(parse_ranges("a", 3), None);
+        assert_eq!(parse_ranges("0,x", 3), None);
+    }
+}
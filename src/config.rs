@@ -0,0 +1,107 @@
+///
+/// =====================================================================
+/// Project Name: rust rm
+/// Description: An enhanced version of the common rm utility.
+/// Author: Robert Pellegrin
+/// Date: 2025-06-22
+/// Version: 0.0.1
+/// License: MIT
+/// Repository:
+/// =====================================================================
+///
+/// Settings read from ~/.config/rrm/config. Each line is `name = value`,
+/// blank lines and lines starting with # are ignored, i.e.
+///
+///     # strftime format for absolute dates in the TUI
+///     date_format = %d %b %Y %H:%M
+///     # start from the vim, arrows or default keys, then remap single actions
+///     keymap = vim
+///     key.restore = r, R
+///
+use chrono::format::StrftimeItems;
+use std::{fs, io, path::PathBuf};
+
+use crate::keymap::Keymap;
+
+#[derive(Default)]
+pub struct Config {
+    /// strftime format for absolute dates in the TUI
+    pub date_format: Option<String>,
+    /// key bindings of the TUI
+    pub keymap: Keymap,
+}
+
+/// Location of the config file.
+pub fn config_path() -> Option<PathBuf> {
+    Some(dirs_next::config_dir()?.join("rrm").join("config"))
+}
+
+/// Reads the config file. A missing file gives the defaults, and invalid
+/// lines are reported and skipped.
+pub fn load() -> Config {
+    let mut config = Config::default();
+    let Some(path) = config_path() else {
+        return config;
+    };
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return config,
+        Err(e) => {
+            eprintln!("rrm: cannot read {}: {}", path.display(), e);
+            return config;
+        }
+    };
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((name, value)) = line.split_once('=') else {
+            eprintln!(
+                "rrm: {}:{}: expected `name = value`",
+                path.display(),
+                number + 1
+            );
+            continue;
+        };
+
+        match name.trim() {
+            "date_format" => {
+                let format = value.trim();
+                // chrono panics when displaying an invalid format, so check it now.
+                if StrftimeItems::new(format).parse().is_ok() {
+                    config.date_format = Some(format.to_string());
+                } else {
+                    eprintln!(
+                        "rrm: {}:{}: invalid date_format '{}'",
+                        path.display(),
+                        number + 1,
+                        format
+                    );
+                }
+            }
+            "keymap" => match Keymap::preset(value.trim()) {
+                Some(keymap) => config.keymap = keymap,
+                None => eprintln!(
+                    "rrm: {}:{}: unknown keymap '{}', expected default, vim or arrows",
+                    path.display(),
+                    number + 1,
+                    value.trim()
+                ),
+            },
+            name if name.starts_with("key.") => {
+                if let Err(e) = config.keymap.bind(&name[4..], value) {
+                    eprintln!("rrm: {}:{}: {}", path.display(), number + 1, e);
+                }
+            }
+            name => eprintln!(
+                "rrm: {}:{}: unknown setting '{}'",
+                path.display(),
+                number + 1,
+                name
+            ),
+        }
+    }
+    config
+}
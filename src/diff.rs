@@ -0,0 +1,332 @@
+///
+/// =====================================================================
+/// Project Name: rust rm
+/// Description: An enhanced version of the common rm utility.
+/// Author: Robert Pellegrin
+/// Date: 2025-06-28
+/// Version: 0.0.1
+/// License: MIT
+/// Repository:
+/// =====================================================================
+///
+/// Compares a trashed file with whatever now exists at its original path,
+/// i.e. after the file was recreated. Text files get a unified diff,
+/// binary files a one line verdict and directories a summary of what was
+/// added, removed or changed below them.
+///
+use similar::{ChangeTag, TextDiff};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, ErrorKind, IsTerminal, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    restore::{self, VersionSelect},
+    trash::{self, TrashItem},
+};
+
+/// Files larger than this are only compared, not diffed.
+const MAX_DIFF_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Lines of context around each change.
+const CONTEXT: usize = 3;
+
+/// What a line of the diff shows, which decides its color.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Header,
+    Hunk,
+    Added,
+    Removed,
+    Context,
+    Note,
+}
+
+pub struct DiffLine {
+    pub kind: Kind,
+    pub text: String,
+}
+
+impl DiffLine {
+    fn new(kind: Kind, text: impl Into<String>) -> Self {
+        Self {
+            kind,
+            text: text.into(),
+        }
+    }
+}
+
+/// Diffs the trash entry `item`, or the path `relative` inside it, against
+/// the same path below its original location.
+pub fn diff_item(item: &TrashItem, relative: &Path) -> io::Result<Vec<DiffLine>> {
+    // Joining an empty path would add a trailing slash.
+    let (trashed, current) = if relative.as_os_str().is_empty() {
+        (item.file_path()?, item.original_path.clone())
+    } else {
+        (
+            item.file_path()?.join(relative),
+            item.original_path.join(relative),
+        )
+    };
+    let deleted = item
+        .deleted_at()
+        .map(|date| date.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    let mut lines = vec![
+        DiffLine::new(
+            Kind::Header,
+            format!("--- {} (deleted {})", trashed.display(), deleted),
+        ),
+        DiffLine::new(Kind::Header, format!("+++ {}", current.display())),
+    ];
+    lines.extend(diff_paths(&trashed, &current)?);
+    Ok(lines)
+}
+
+/// Diffs the entry `source`, a path relative to files/ such as
+/// build-scripts/deploy.sh, against its original location.
+pub fn diff_source(source: &Path) -> io::Result<Vec<DiffLine>> {
+    let mut components = source.components();
+    let name = components
+        .next()
+        .map(|name| name.as_os_str().to_string_lossy().into_owned())
+        .unwrap_or_default();
+    diff_item(&trash::read_item(&name)?, components.as_path())
+}
+
+/// Body of the diff between `old` in the trash and `new`.
+fn diff_paths(old: &Path, new: &Path) -> io::Result<Vec<DiffLine>> {
+    let old_meta = fs::symlink_metadata(old)?;
+    let new_meta = match fs::symlink_metadata(new) {
+        Ok(meta) => meta,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            return Ok(vec![DiffLine::new(
+                Kind::Note,
+                "nothing exists at the original path anymore",
+            )]);
+        }
+        Err(e) => return Err(e),
+    };
+
+    match (old_meta.is_dir(), new_meta.is_dir()) {
+        (true, true) => diff_dirs(old, new),
+        (false, false) => diff_files(old, new),
+        (true, false) => Ok(vec![DiffLine::new(
+            Kind::Note,
+            "the trashed entry is a directory, the original path is not",
+        )]),
+        (false, true) => Ok(vec![DiffLine::new(
+            Kind::Note,
+            "the original path is now a directory",
+        )]),
+    }
+}
+
+fn diff_files(old: &Path, new: &Path) -> io::Result<Vec<DiffLine>> {
+    let (old_len, new_len) = (fs::metadata(old)?.len(), fs::metadata(new)?.len());
+    if old_len > MAX_DIFF_BYTES || new_len > MAX_DIFF_BYTES {
+        let verdict = if same_contents(old, new)? {
+            "files are identical"
+        } else {
+            "files differ"
+        };
+        return Ok(vec![DiffLine::new(
+            Kind::Note,
+            format!(
+                "{} ({} and {}, too large to diff)",
+                verdict,
+                trash::format_size(old_len),
+                trash::format_size(new_len)
+            ),
+        )]);
+    }
+
+    let (old_bytes, new_bytes) = (fs::read(old)?, fs::read(new)?);
+    if old_bytes == new_bytes {
+        return Ok(vec![DiffLine::new(Kind::Note, "no differences")]);
+    }
+    // A NUL byte means binary, the same heuristic as grep and git. Text that
+    // is not UTF-8 cannot be shown line by line either.
+    let (Ok(old_text), Ok(new_text)) = (str::from_utf8(&old_bytes), str::from_utf8(&new_bytes))
+    else {
+        return Ok(binary_note(old_len, new_len));
+    };
+    if old_text.contains('\0') || new_text.contains('\0') {
+        return Ok(binary_note(old_len, new_len));
+    }
+
+    let diff = TextDiff::from_lines(old_text, new_text);
+    let mut lines = Vec::new();
+    for hunk in diff.unified_diff().context_radius(CONTEXT).iter_hunks() {
+        lines.push(DiffLine::new(Kind::Hunk, hunk.header().to_string()));
+        for change in hunk.iter_changes() {
+            let (kind, sign) = match change.tag() {
+                ChangeTag::Delete => (Kind::Removed, '-'),
+                ChangeTag::Insert => (Kind::Added, '+'),
+                ChangeTag::Equal => (Kind::Context, ' '),
+            };
+            let text = change.value().trim_end_matches(['\n', '\r']);
+            lines.push(DiffLine::new(kind, format!("{}{}", sign, text)));
+            if change.missing_newline() {
+                lines.push(DiffLine::new(Kind::Note, "\\ No newline at end of file"));
+            }
+        }
+    }
+    Ok(lines)
+}
+
+fn binary_note(old_len: u64, new_len: u64) -> Vec<DiffLine> {
+    vec![DiffLine::new(
+        Kind::Note,
+        format!(
+            "binary files differ ({} and {})",
+            trash::format_size(old_len),
+            trash::format_size(new_len)
+        ),
+    )]
+}
+
+/// True if both files hold the same bytes. Reads them in chunks so that
+/// large files do not have to fit in memory.
+fn same_contents(old: &Path, new: &Path) -> io::Result<bool> {
+    if fs::metadata(old)?.len() != fs::metadata(new)?.len() {
+        return Ok(false);
+    }
+    let (mut old, mut new) = (fs::File::open(old)?, fs::File::open(new)?);
+    let (mut a, mut b) = (vec![0; 64 * 1024], vec![0; 64 * 1024]);
+    loop {
+        let n = old.read(&mut a)?;
+        if n == 0 {
+            return Ok(true);
+        }
+        new.read_exact(&mut b[..n])?;
+        if a[..n] != b[..n] {
+            return Ok(false);
+        }
+    }
+}
+
+/// What a path inside a compared directory is.
+enum Entry {
+    Dir,
+    File,
+    Link(PathBuf),
+}
+
+/// Summary of the differences between two directory trees.
+fn diff_dirs(old: &Path, new: &Path) -> io::Result<Vec<DiffLine>> {
+    let (mut old_entries, mut new_entries) = (BTreeMap::new(), BTreeMap::new());
+    walk(old, Path::new(""), &mut old_entries)?;
+    walk(new, Path::new(""), &mut new_entries)?;
+
+    let mut lines = Vec::new();
+    let (mut removed, mut added, mut changed) = (0, 0, 0);
+    for (relative, entry) in &old_entries {
+        match new_entries.remove(relative) {
+            None => {
+                removed += 1;
+                lines.push(DiffLine::new(
+                    Kind::Removed,
+                    format!("- {} (only in the trash)", relative.display()),
+                ));
+            }
+            Some(other) => {
+                let change = match (entry, &other) {
+                    (Entry::Dir, Entry::Dir) => None,
+                    (Entry::File, Entry::File) => {
+                        (!same_contents(&old.join(relative), &new.join(relative))?).then_some("")
+                    }
+                    (Entry::Link(a), Entry::Link(b)) => (a != b).then_some(" (link target)"),
+                    _ => Some(" (type)"),
+                };
+                if let Some(what) = change {
+                    changed += 1;
+                    lines.push(DiffLine::new(
+                        Kind::Hunk,
+                        format!("~ {}{}", relative.display(), what),
+                    ));
+                }
+            }
+        }
+    }
+    for relative in new_entries.keys() {
+        added += 1;
+        lines.push(DiffLine::new(
+            Kind::Added,
+            format!("+ {} (only at the original path)", relative.display()),
+        ));
+    }
+    // Paths are sorted within each group; show them in one sorted list.
+    lines.sort_by(|a, b| a.text[2..].cmp(&b.text[2..]));
+
+    lines.push(DiffLine::new(
+        Kind::Note,
+        if lines.is_empty() {
+            "no differences".to_string()
+        } else {
+            format!(
+                "{} changed, {} only in the trash, {} only at the original path",
+                changed, removed, added
+            )
+        },
+    ));
+    Ok(lines)
+}
+
+/// Collects every path below `dir`, relative to where the walk started.
+fn walk(dir: &Path, relative: &Path, entries: &mut BTreeMap<PathBuf, Entry>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let relative = relative.join(entry.file_name());
+        let kind = entry.file_type()?;
+        if kind.is_dir() {
+            walk(&path, &relative, entries)?;
+            entries.insert(relative, Entry::Dir);
+        } else if kind.is_symlink() {
+            entries.insert(relative, Entry::Link(fs::read_link(&path)?));
+        } else {
+            entries.insert(relative, Entry::File);
+        }
+    }
+    Ok(())
+}
+
+/// Prints the diff, colored when writing to a terminal.
+pub fn print(lines: &[DiffLine]) {
+    let color = io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    for line in lines {
+        let code = match line.kind {
+            Kind::Header => "1",
+            Kind::Hunk => "36",
+            Kind::Added => "32",
+            Kind::Removed => "31",
+            Kind::Note => "2",
+            Kind::Context => "",
+        };
+        if color && !code.is_empty() {
+            println!("\x1b[{}m{}\x1b[0m", code, line.text);
+        } else {
+            println!("{}", line.text);
+        }
+    }
+}
+
+/// Handles `rrm --diff`: `target` is a trash entry, the path it was
+/// deleted from, or a path inside a trashed directory.
+pub fn run(target: &str, select: VersionSelect) -> io::Result<()> {
+    let lines = match restore::find_item(target, select) {
+        Ok(Some(item)) => diff_item(&item, Path::new(""))?,
+        Ok(None) => return Ok(()),
+        Err(e) if e.kind() == ErrorKind::NotFound => match restore::find_inner(target)? {
+            Some((item, relative)) => diff_item(&item, &relative)?,
+            None => return Err(e),
+        },
+        Err(e) => return Err(e),
+    };
+    print(&lines);
+    Ok(())
+}
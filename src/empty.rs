@@ -10,11 +10,16 @@
 /// =====================================================================
 ///
 use std::{
-    env, fs,
-    io::{self, Write},
+    collections::HashSet,
+    env, fs, io,
     path::{Path, PathBuf},
 };
 
+use crate::{
+    mv, restore,
+    trash::{self, TrashItem},
+};
+
 /// Deletes all contents inside the given directory (but not the directory
 /// itself).
 fn delete_directory_contents(dir: &Path) -> io::Result<()> {
@@ -32,22 +37,6 @@ fn delete_directory_contents(dir: &Path) -> io::Result<()> {
     Ok(())
 }
 
-/// Prompts the user for a yes/no confirmation.
-/// Returns true if the user enters 'y' or 'Y'.
-fn confirm(prompt: &str) -> io::Result<bool> {
-    print!("{} [y/N]: ", prompt);
-
-    // Make sure prompt shows up before input
-    io::stdout().flush()?;
-
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-
-    let response = input.trim().to_lowercase();
-
-    Ok(response == "y")
-}
-
 /// Empties the user's trash directory located at:
 /// ~/.local/share/Trash/{files,info}
 pub fn empty_trash() -> io::Result<()> {
@@ -55,20 +44,92 @@ pub fn empty_trash() -> io::Result<()> {
         .map_err(|_| io::Error::new(io::ErrorKind::NotFound, "HOME env var not set"))?;
     let trash_base = PathBuf::from(home).join(".local/share/Trash");
 
-    let files_dir = trash_base.join("files");
     let info_dir = trash_base.join("info");
 
     let count = fs::read_dir(&info_dir)?.count();
 
     let prompt = format!("Permanently delete all {} file(s) in the trash?", count);
 
-    if !confirm(&prompt)? {
+    if !mv::confirm(&prompt)? {
         println!("Cancelled");
         return Ok(());
     }
 
-    delete_directory_contents(&files_dir)?;
-    delete_directory_contents(&info_dir)?;
+    delete_all()
+}
+
+/// Deletes everything in the trash without asking.
+pub fn delete_all() -> io::Result<()> {
+    delete_directory_contents(&trash::files_dir()?)?;
+    delete_directory_contents(&trash::info_dir()?)?;
+
+    // Nothing is left for the directorysizes cache to describe.
+    match fs::remove_file(trash::trash_dir()?.join("directorysizes")) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Permanently deletes a single trash entry along with its .trashinfo file.
+pub fn purge_item(item: &TrashItem) -> io::Result<()> {
+    let path = item.file_path()?;
+    if fs::symlink_metadata(&path)?.is_dir() {
+        fs::remove_dir_all(&path)?;
+    } else {
+        fs::remove_file(&path)?;
+    }
+    trash::remove_info(item)?;
+
+    trash::sync_directory_size(item);
+    Ok(())
+}
+
+/// Permanently deletes the trash entries named by `targets`. Each target is
+/// either a name inside the trash or a path that was deleted, in which case
+/// every trashed version of that path is deleted.
+pub fn purge(targets: &[String], force: bool, verbose: bool) -> io::Result<()> {
+    let mut items = Vec::new();
+    for target in targets {
+        let versions = restore::versions_of(&trash::absolute_path(Path::new(target))?)?;
+        if !versions.is_empty() {
+            items.extend(versions);
+            continue;
+        }
+        match trash::read_item(target) {
+            Ok(item) => items.push(item),
+            Err(e) => eprintln!("rrm: cannot purge '{}': {}", target, e),
+        }
+    }
+
+    let mut seen = HashSet::new();
+    items.retain(|item| seen.insert(item.name.clone()));
+    if items.is_empty() {
+        return Ok(());
+    }
+
+    let size: u64 = items
+        .iter()
+        .filter_map(|item| item.file_path().ok())
+        .map(|path| trash::disk_usage(&path))
+        .sum();
+    let prompt = format!(
+        "Permanently delete {} item(s) ({})?",
+        items.len(),
+        trash::format_size(size)
+    );
+
+    if !force && !mv::confirm(&prompt)? {
+        println!("Cancelled");
+        return Ok(());
+    }
+
+    for item in &items {
+        match purge_item(item) {
+            Ok(()) if verbose => println!("purged '{}'", item.name),
+            Ok(()) => {}
+            Err(e) => eprintln!("rrm: cannot purge '{}': {}", item.name, e),
+        }
+    }
 
     Ok(())
 }
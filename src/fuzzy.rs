@@ -0,0 +1,74 @@
+///
+/// =====================================================================
+/// Project Name: rust rm
+/// Description: An enhanced version of the common rm utility.
+/// Author: Robert Pellegrin
+/// Date: 2025-06-16
+/// Version: 0.0.1
+/// License: MIT
+/// Repository:
+/// =====================================================================
+///
+use std::iter;
+
+/// Result of matching a search pattern against a piece of text.
+pub struct FuzzyMatch {
+    /// Higher is better
+    pub score: i64,
+    /// Char indexes of `text` that matched the pattern, in order
+    pub positions: Vec<usize>,
+}
+
+/// Matches `pattern` as a case-insensitive subsequence of `text`, i.e. "cfy"
+/// matches "config.yaml". Consecutive characters and characters at the start
+/// of a word score higher. Returns None if `pattern` is not a subsequence.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+    if pattern.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut positions = Vec::with_capacity(pattern.len());
+    let mut score = 0;
+    let mut next = 0;
+
+    for (i, c) in chars.iter().enumerate() {
+        if next == pattern.len() {
+            break;
+        }
+        if !c.to_lowercase().eq(iter::once(pattern[next])) {
+            continue;
+        }
+
+        score += 1;
+        if positions.last().is_some_and(|&last| last + 1 == i) {
+            score += 5;
+        }
+        if i == 0 || matches!(chars[i - 1], '/' | '.' | '_' | '-' | ' ') {
+            score += 3;
+        }
+
+        positions.push(i);
+        next += 1;
+    }
+
+    if next < pattern.len() {
+        return None;
+    }
+
+    // Prefer matches that are tight and close to the end, i.e. in the file
+    // name rather than in a parent directory.
+    let first = positions[0];
+    let last = positions[positions.len() - 1];
+    #[allow(clippy::cast_possible_wrap)]
+    let penalty = (last - first) as i64 + (chars.len() - last) as i64 / 4;
+
+    Some(FuzzyMatch {
+        score: score * 10 - penalty,
+        positions,
+    })
+}
@@ -0,0 +1,430 @@
+///
+/// =====================================================================
+/// Project Name: rust rm
+/// Description: An enhanced version of the common rm utility.
+/// Author: Robert Pellegrin
+/// Date: 2025-06-24
+/// Version: 0.0.1
+/// License: MIT
+/// Repository:
+/// =====================================================================
+///
+/// Key bindings of the trash TUI. The footer and the help overlay are both
+/// generated from the active keymap, so they always list the keys that work.
+///
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::fmt;
+
+/// Something a key can do in the trash table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Down,
+    Up,
+    Left,
+    Right,
+    ScrollLeft,
+    ScrollRight,
+    Search,
+    NextMatch,
+    PreviousMatch,
+    Open,
+    NextSort,
+    ReverseSort,
+    Mark,
+    Visual,
+    InvertMarks,
+    Restore,
+    RestoreTo,
+    Delete,
+    Empty,
+    Preview,
+    Expand,
+    Collapse,
+    Info,
+    Diff,
+    Pager,
+    Editor,
+    Copy,
+    CopyLocation,
+    Group,
+    Dates,
+    NextPalette,
+    PreviousPalette,
+    Help,
+}
+
+impl Action {
+    /// Every action, in the order the help overlay lists them.
+    pub const ALL: [Action; 34] = [
+        Action::Quit,
+        Action::Down,
+        Action::Up,
+        Action::Left,
+        Action::Right,
+        Action::ScrollLeft,
+        Action::ScrollRight,
+        Action::Search,
+        Action::NextMatch,
+        Action::PreviousMatch,
+        Action::Open,
+        Action::NextSort,
+        Action::ReverseSort,
+        Action::Mark,
+        Action::Visual,
+        Action::InvertMarks,
+        Action::Restore,
+        Action::RestoreTo,
+        Action::Delete,
+        Action::Empty,
+        Action::Preview,
+        Action::Expand,
+        Action::Collapse,
+        Action::Info,
+        Action::Diff,
+        Action::Pager,
+        Action::Editor,
+        Action::Copy,
+        Action::CopyLocation,
+        Action::Group,
+        Action::Dates,
+        Action::NextPalette,
+        Action::PreviousPalette,
+        Action::Help,
+    ];
+
+    /// Name used for the action in the config file, i.e. key.restore_to = t
+    pub const fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Down => "down",
+            Action::Up => "up",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::ScrollLeft => "scroll_left",
+            Action::ScrollRight => "scroll_right",
+            Action::Search => "search",
+            Action::NextMatch => "next_match",
+            Action::PreviousMatch => "previous_match",
+            Action::Open => "open",
+            Action::NextSort => "sort",
+            Action::ReverseSort => "reverse_sort",
+            Action::Mark => "mark",
+            Action::Visual => "visual",
+            Action::InvertMarks => "invert_marks",
+            Action::Restore => "restore",
+            Action::RestoreTo => "restore_to",
+            Action::Delete => "delete",
+            Action::Empty => "empty",
+            Action::Preview => "preview",
+            Action::Expand => "expand",
+            Action::Collapse => "collapse",
+            Action::Info => "info",
+            Action::Diff => "diff",
+            Action::Pager => "pager",
+            Action::Editor => "editor",
+            Action::Copy => "copy",
+            Action::CopyLocation => "copy_location",
+            Action::Group => "group",
+            Action::Dates => "dates",
+            Action::NextPalette => "next_palette",
+            Action::PreviousPalette => "previous_palette",
+            Action::Help => "help",
+        }
+    }
+
+    /// Short label for the footer.
+    pub const fn label(self) -> &'static str {
+        match self {
+            Action::RestoreTo => "restore to...",
+            Action::NextSort => "sort",
+            Action::ScrollLeft => "scroll left",
+            Action::ScrollRight => "scroll right",
+            Action::InvertMarks => "invert",
+            Action::NextPalette => "next palette",
+            Action::PreviousPalette => "previous palette",
+            Action::NextMatch => "next match",
+            Action::PreviousMatch => "previous match",
+            Action::ReverseSort => "reverse sort",
+            Action::CopyLocation => "copy location",
+            action => action.name(),
+        }
+    }
+
+    /// What the action does, for the help overlay.
+    pub const fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "quit, or leave visual mode, the search or a directory",
+            Action::Down => "move down",
+            Action::Up => "move up",
+            Action::Left => "move to the previous column",
+            Action::Right => "move to the next column",
+            Action::ScrollLeft => "scroll a cut off cell to the left",
+            Action::ScrollRight => "scroll a cut off cell to the right",
+            Action::Search => "fuzzy search names and paths",
+            Action::NextMatch => "move to the next match",
+            Action::PreviousMatch => "move to the previous match",
+            Action::Open => "open a directory, fold a group, or sort by the column",
+            Action::NextSort => "sort by the next column",
+            Action::ReverseSort => "reverse the sort order",
+            Action::Mark => "mark or unmark the row",
+            Action::Visual => "start or finish marking a range",
+            Action::InvertMarks => "invert the marks",
+            Action::Restore => "restore to the original location",
+            Action::RestoreTo => "restore to another directory",
+            Action::Delete => "permanently delete",
+            Action::Empty => "permanently delete everything in the trash",
+            Action::Preview => "show or hide the preview pane",
+            Action::Expand => "expand directories in the preview",
+            Action::Collapse => "collapse directories in the preview",
+            Action::Info => "show details of the entry",
+            Action::Diff => "diff the file against the one now at its original path",
+            Action::Pager => "view the file in $PAGER without restoring it",
+            Action::Editor => "open the file read-only in $EDITOR",
+            Action::Copy => "copy the selected cell to the clipboard",
+            Action::CopyLocation => "copy the location of the file in the trash",
+            Action::Group => "group entries by original directory",
+            Action::Dates => "switch between relative and full dates",
+            Action::NextPalette => "next color palette",
+            Action::PreviousPalette => "previous color palette",
+            Action::Help => "show this help",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Action::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+/// A key with its modifiers, i.e. Shift+→
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    const fn plain(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    const fn char(c: char) -> Self {
+        Self::plain(KeyCode::Char(c))
+    }
+
+    const fn shift(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::SHIFT,
+        }
+    }
+
+    fn matches(self, event: &KeyEvent) -> bool {
+        let mask = KeyModifiers::SHIFT | KeyModifiers::CONTROL | KeyModifiers::ALT;
+        let (mut ours, mut theirs) = (self.modifiers & mask, event.modifiers & mask);
+        // Shift is already part of a character, i.e. 'L'
+        if matches!(self.code, KeyCode::Char(_)) {
+            ours.remove(KeyModifiers::SHIFT);
+            theirs.remove(KeyModifiers::SHIFT);
+        }
+        self.code == event.code && ours == theirs
+    }
+
+    /// Parses a key such as q, Enter, Space, Shift+Right or Ctrl+d.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        // A lone + is a key, not a separator.
+        let (mods, name) = match text.strip_suffix("++") {
+            Some(mods) => (mods, "+"),
+            None if text == "+" => ("", "+"),
+            None => match text.rsplit_once('+') {
+                Some((mods, name)) => (mods, name),
+                None => ("", text),
+            },
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for part in mods.split('+').filter(|part| !part.is_empty()) {
+            modifiers |= match part.to_lowercase().as_str() {
+                "shift" => KeyModifiers::SHIFT,
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                _ => return None,
+            };
+        }
+
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match name.to_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "backspace" => KeyCode::Backspace,
+                "tab" => KeyCode::Tab,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "delete" => KeyCode::Delete,
+                f => KeyCode::F(f.strip_prefix('f')?.parse().ok()?),
+            },
+        };
+        Some(Self { code, modifiers })
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            KeyCode::Delete => write!(f, "Delete"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Actions and the keys bound to them.
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<Key>)>,
+}
+
+impl Default for Keymap {
+    /// Both vim keys and arrow keys.
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .into_iter()
+            .map(|action| {
+                let keys = match action {
+                    Action::Quit => vec![Key::char('q'), Key::plain(KeyCode::Esc)],
+                    Action::Down => vec![Key::char('j'), Key::plain(KeyCode::Down)],
+                    Action::Up => vec![Key::char('k'), Key::plain(KeyCode::Up)],
+                    Action::Left => vec![Key::char('h'), Key::plain(KeyCode::Left)],
+                    Action::Right => vec![Key::char('l'), Key::plain(KeyCode::Right)],
+                    Action::ScrollLeft => vec![Key::char('<')],
+                    Action::ScrollRight => vec![Key::char('>')],
+                    Action::Search => vec![Key::char('/')],
+                    Action::NextMatch => vec![Key::char('n')],
+                    Action::PreviousMatch => vec![Key::char('N')],
+                    Action::Open => vec![Key::plain(KeyCode::Enter)],
+                    Action::NextSort => vec![Key::char('s')],
+                    Action::ReverseSort => vec![Key::char('S')],
+                    Action::Mark => vec![Key::char(' ')],
+                    Action::Visual => vec![Key::char('V')],
+                    Action::InvertMarks => vec![Key::char('*')],
+                    Action::Restore => vec![Key::char('r')],
+                    Action::RestoreTo => vec![Key::char('t')],
+                    Action::Delete => vec![Key::char('d')],
+                    Action::Empty => vec![Key::char('e')],
+                    Action::Preview => vec![Key::char('p')],
+                    Action::Expand => vec![Key::char('+')],
+                    Action::Collapse => vec![Key::char('-')],
+                    Action::Info => vec![Key::char('i')],
+                    Action::Diff => vec![Key::char('D')],
+                    Action::Pager => vec![Key::char('o')],
+                    Action::Editor => vec![Key::char('O')],
+                    Action::Copy => vec![Key::char('y')],
+                    Action::CopyLocation => vec![Key::char('Y')],
+                    Action::Group => vec![Key::char('g')],
+                    Action::Dates => vec![Key::char('a')],
+                    Action::NextPalette => vec![Key::shift(KeyCode::Right), Key::char('L')],
+                    Action::PreviousPalette => vec![Key::shift(KeyCode::Left), Key::char('H')],
+                    Action::Help => vec![Key::char('?')],
+                };
+                (action, keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Starts from a named preset: "default", "vim" (hjkl only) or "arrows"
+    /// (arrow keys only).
+    pub fn preset(name: &str) -> Option<Self> {
+        let mut keymap = Self::default();
+        let keep: fn(&Key) -> bool = match name {
+            "default" => return Some(keymap),
+            "vim" => |key| matches!(key.code, KeyCode::Char(_)),
+            "arrows" => |key| !matches!(key.code, KeyCode::Char(_)),
+            _ => return None,
+        };
+        for (action, keys) in &mut keymap.bindings {
+            if matches!(
+                action,
+                Action::Down | Action::Up | Action::Left | Action::Right
+            ) {
+                keys.retain(keep);
+            }
+        }
+        Some(keymap)
+    }
+
+    /// Binds `keys`, a comma separated list such as "r, Enter", to the action
+    /// called `name`, replacing its previous keys. Returns an error message
+    /// if either is invalid.
+    pub fn bind(&mut self, name: &str, keys: &str) -> Result<(), String> {
+        let action = Action::from_name(name).ok_or_else(|| format!("unknown action '{}'", name))?;
+        let keys = keys
+            .split(',')
+            .filter(|key| !key.trim().is_empty())
+            .map(|key| Key::parse(key).ok_or_else(|| format!("unknown key '{}'", key.trim())))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (bound, bound_keys) in &mut self.bindings {
+            if *bound == action {
+                *bound_keys = keys.clone();
+            } else {
+                // A key does one thing only
+                bound_keys.retain(|key| !keys.contains(key));
+            }
+        }
+        Ok(())
+    }
+
+    /// The action bound to a key press, if any.
+    pub fn action(&self, event: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.iter().any(|key| key.matches(event)))
+            .map(|(action, _)| *action)
+    }
+
+    /// Keys bound to `action`, in the order they were configured.
+    pub fn keys(&self, action: Action) -> &[Key] {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map_or(&[], |(_, keys)| keys.as_slice())
+    }
+}
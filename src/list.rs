@@ -0,0 +1,64 @@
+///
+/// =====================================================================
+/// Project Name: rust rm
+/// Description: An enhanced version of the common rm utility.
+/// Author: Robert Pellegrin
+/// Date: 2025-06-09
+/// Version: 0.0.1
+/// License: MIT
+/// Repository:
+/// =====================================================================
+///
+use std::io;
+
+use crate::trash::{self, TrashItem};
+
+/// Reads the trash sorted by deletion date, oldest first.
+fn sorted_items() -> io::Result<Vec<TrashItem>> {
+    let mut items = trash::list_items()?;
+    items.sort_by_cached_key(TrashItem::deletion_key);
+    Ok(items)
+}
+
+/// Prints one line per trash entry with its deletion date, size and original
+/// path.
+pub fn print_trash_list() -> io::Result<()> {
+    for item in sorted_items()? {
+        let date = item
+            .deleted_at()
+            .map(|date| date.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "Unknown".to_string());
+        let size = trash::format_size(trash::disk_usage(&item.file_path()?));
+        println!("{}  {:>10}  {}", date, size, item.original_path.display());
+    }
+    Ok(())
+}
+
+/// Prints a summary of the trash: entry counts, total size and the range of
+/// deletion dates.
+pub fn print_trash_stats() -> io::Result<()> {
+    let items = sorted_items()?;
+    let paths: Vec<_> = items
+        .iter()
+        .filter_map(|item| item.file_path().ok())
+        .collect();
+
+    let directories = paths.iter().filter(|path| path.is_dir()).count();
+    let size: u64 = paths.iter().map(|path| trash::disk_usage(path)).sum();
+    let date = |item: Option<&TrashItem>| {
+        item.and_then(TrashItem::deleted_at)
+            .map_or_else(|| "-".to_string(), |date| date.to_string())
+    };
+
+    println!("Location:     {}", trash::trash_dir()?.display());
+    println!(
+        "Items:        {} ({} files, {} directories)",
+        items.len(),
+        items.len() - directories,
+        directories
+    );
+    println!("Total size:   {}", trash::format_size(size));
+    println!("Oldest:       {}", date(items.first()));
+    println!("Newest:       {}", date(items.last()));
+    Ok(())
+}
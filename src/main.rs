@@ -10,19 +10,57 @@
 /// =====================================================================
 ///
 mod args;
+mod clipboard;
+mod compat;
+mod config;
+mod diff;
 mod empty;
+mod fuzzy;
+mod keymap;
+mod list;
 mod mv;
+mod open;
+mod preview;
+mod restore;
+mod trash;
 mod view;
+mod watch;
 
-use args::Args;
-use clap::Parser;
+use args::{Args, Command, RestoreOptions, RmOptions};
+use clap::{CommandFactory, Parser};
 use rayon::prelude::*;
+use std::io;
 
 fn main() {
+    // Invoked through a trash-cli symlink such as trash-put
+    if let Some(tool) = compat::invoked_as() {
+        std::process::exit(compat::run(tool));
+    }
+
     let args = Args::parse();
 
+    if let Some(command) = args.command {
+        run_command(command, args.verbose);
+        return;
+    }
+
+    // --version doubles as the version picker for --restore and --diff
+    if args.restore_options.version.is_some() && !args.restoring() && args.diff.is_none() {
+        print!("{}", Args::command().render_version());
+        return;
+    }
+
     if args.view_trash {
-        view::list_trash_contents_table();
+        view::list_trash_contents_table(args.restore_options.on_conflict);
+        return;
+    }
+
+    if let Some(target) = &args.diff {
+        let select = args.restore_options.version_select();
+        report(
+            diff::run(target, select),
+            &format!("cannot diff '{}'", target),
+        );
         return;
     }
 
@@ -33,32 +71,122 @@ fn main() {
         return;
     }
 
+    if args.restore_options.restore_since.is_some() {
+        restore_files(&[], &args.restore_options, args.verbose);
+        return;
+    }
+
     // If no files/dirs were specified, inform user and exit.
-    if args.files.len() < 1 {
-        view::list_trash_contents_table();
+    if args.files.is_empty() {
+        view::list_trash_contents_table(args.restore_options.on_conflict);
+        return;
+    }
+
+    if args.restoring() {
+        restore_files(&args.files, &args.restore_options, args.verbose);
         return;
     }
 
-    if args.interactive {
-        process_files_serial(args.files, &Args::parse());
+    trash_files(&args.files, &args.rm, args.verbose);
+}
+
+/// Runs one of the `rrm <command>` subcommands.
+fn run_command(command: Command, verbose: bool) {
+    match command {
+        Command::Put { rm, files } => trash_files(&files, &rm, verbose),
+        Command::List => report(list::print_trash_list(), "cannot list trash"),
+        Command::Restore { options, items } => restore_files(&items, &options, verbose),
+        Command::Purge { force, items } => {
+            report(empty::purge(&items, force, verbose), "cannot purge");
+        }
+        Command::Empty => report(empty::empty_trash(), "Failed to empty trash"),
+        Command::View { on_conflict } => view::list_trash_contents_table(on_conflict),
+        Command::Stats => report(list::print_trash_stats(), "cannot read trash"),
+    }
+}
+
+fn report(result: io::Result<()>, context: &str) {
+    if let Err(e) = result {
+        eprintln!("rrm: {}: {}", context, e);
+    }
+}
+
+fn trash_files(files: &[String], rm: &RmOptions, verbose: bool) {
+    if rm.interactive {
+        process_files_serial(files, rm, verbose);
     } else {
-        process_files_parallel(args.files, &Args::parse());
+        process_files_parallel(files, rm, verbose);
     }
 }
 
-fn process_files_parallel(files: Vec<String>, args: &Args) {
+fn process_files_parallel(files: &[String], rm: &RmOptions, verbose: bool) {
     files.par_iter().for_each(|arg| {
-        if let Err(e) = mv::move_to_trash(arg, args) {
+        if let Err(e) = mv::move_to_trash(arg, rm, verbose) {
             eprintln!("rrm: cannot remove '{}': {}", arg, e);
         }
     });
 }
 
-fn process_files_serial(files: Vec<String>, args: &Args) {
+fn process_files_serial(files: &[String], rm: &RmOptions, verbose: bool) {
     eprintln!("WARNING: interactive flag disabled parallelism");
     files.iter().for_each(|arg| {
-        if let Err(e) = mv::move_to_trash(arg, args) {
+        if let Err(e) = mv::move_to_trash(arg, rm, verbose) {
             eprintln!("rrm: cannot remove '{}': {}", arg, e);
         }
     });
 }
+
+fn restore_files(names: &[String], options: &RestoreOptions, verbose: bool) {
+    if let Some(since) = options.restore_since {
+        let under = match options
+            .under
+            .as_deref()
+            .map(trash::absolute_path)
+            .transpose()
+        {
+            Ok(under) => under,
+            Err(e) => {
+                eprintln!("rrm: invalid --under directory: {}", e);
+                return;
+            }
+        };
+        let result = restore::restore_since(
+            since,
+            under.as_deref(),
+            options.on_conflict,
+            options.dry_run,
+            verbose,
+        );
+        report(result, "cannot restore");
+        return;
+    }
+
+    let dest_dir = match (&options.to, options.here) {
+        (Some(dir), _) => Some(dir.clone()),
+        (None, true) => match std::env::current_dir() {
+            Ok(dir) => Some(dir),
+            Err(e) => {
+                eprintln!("rrm: cannot determine current directory: {}", e);
+                return;
+            }
+        },
+        (None, false) => None,
+    };
+
+    for name in names {
+        let result = restore::restore_target(
+            name,
+            options.version_select(),
+            dest_dir.as_deref(),
+            options.on_conflict,
+        );
+        match result {
+            Ok(Some(target)) if verbose => {
+                println!("restored '{}' to '{}'", name, target.display());
+            }
+            Ok(None) if verbose => println!("skipped '{}'", name),
+            Ok(_) => {}
+            Err(e) => eprintln!("rrm: cannot restore '{}': {}", name, e),
+        }
+    }
+}
@@ -11,26 +11,18 @@
 ///
 use chrono::Local;
 use std::{
+    env,
     fs::{self, File},
     io::{self, ErrorKind, Write},
     path::{Path, PathBuf},
 };
 
-use crate::args::Args;
+use crate::{args::RmOptions, trash};
 
 /// Moves a file (or directory) to the trash.
-pub fn move_to_trash(source: &str, args: &Args) -> std::io::Result<()> {
-    let trash_dir_files = dirs_next::home_dir()
-        .map(|home| home.join(".local/share/Trash/files"))
-        .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "Could not determine home directory"))?;
-
-    // Get the filename from the source path
+pub fn move_to_trash(source: &str, args: &RmOptions, verbose: bool) -> std::io::Result<()> {
     let source_path = Path::new(source);
 
-    // TODO --> If the directory does not exist, silently create it.
-    // Ensure the directory exists
-    fs::create_dir_all(&trash_dir_files)?; // No error if it already exists
-
     // Check if the file is a symlink before proceeding. If it is, delete it instead of attempting
     // to move it to the trash.
     if fs::symlink_metadata(source)?.file_type().is_symlink() {
@@ -43,16 +35,6 @@ pub fn move_to_trash(source: &str, args: &Args) -> std::io::Result<()> {
         return Err(io::Error::new(ErrorKind::InvalidInput, "Is a directory"));
     }
 
-    let filename = match source_path.file_name() {
-        Some(name) => name.to_string_lossy().into_owned(), // Convert OsStr to String
-        None => {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                "Source path does not contain a valid filename",
-            ));
-        }
-    };
-
     if args.skip_trash {
         if source_path.is_dir() {
             fs::remove_dir_all(source)?;
@@ -62,27 +44,6 @@ pub fn move_to_trash(source: &str, args: &Args) -> std::io::Result<()> {
         return Ok(());
     }
 
-    // Get a resolved trash path that avoids naming conflicts
-    let trash_path = resolve_naming_conflict(&trash_dir_files, &filename);
-
-    match fs::canonicalize(source_path) {
-        Ok(abs_path) => {
-            if let Some(path_str) = abs_path.to_str() {
-                if let Err(e) = create_metadata_file(path_str) {
-                    eprintln!("Error creating metadata file for {}: {}", source, e);
-                    return Ok(());
-                }
-            } else {
-                eprintln!("Path contains invalid UTF-8: {}", abs_path.display());
-                return Ok(());
-            }
-        }
-        Err(e) => {
-            eprintln!("Error resolving path for {}: {}", source, e);
-            return Ok(());
-        }
-    }
-
     if args.interactive {
         let prompt = format!("move '{}' to the trash?", source);
         if !confirm(&prompt)? {
@@ -90,67 +51,106 @@ pub fn move_to_trash(source: &str, args: &Args) -> std::io::Result<()> {
         }
     }
 
-    if args.verbose {
+    trash(source_path)?;
+
+    if verbose {
         println!("removed '{}'", source);
     }
 
-    // Try to rename (move) the file to the trash directory
-    fs::rename(source, &trash_path)?;
+    Ok(())
+}
 
-    if args.verbose {
-        println!("removed '{}'", source);
+/// Moves `source` into the trash without any of the rm-style checks, writing
+/// its .trashinfo file first. Returns the new location inside files/.
+pub fn trash(source: &Path) -> io::Result<PathBuf> {
+    let trash_dir_files = trash::files_dir()?;
+    let trash_dir_metadata = trash::info_dir()?;
+    fs::create_dir_all(&trash_dir_files)?;
+    fs::create_dir_all(&trash_dir_metadata)?;
+
+    let filename = source
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| {
+            io::Error::new(
+                ErrorKind::InvalidInput,
+                "Source path does not contain a valid filename",
+            )
+        })?;
+
+    // Only canonicalize the parent so that a symlink is recorded as itself
+    // rather than as its target.
+    let abs_path = match source.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => fs::canonicalize(parent)?,
+        _ => env::current_dir()?,
     }
+    .join(&filename);
+    let path_str = abs_path.to_str().ok_or_else(|| {
+        io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("Path contains invalid UTF-8: {}", abs_path.display()),
+        )
+    })?;
+
+    // The entry in files/ and its .trashinfo must share a name, so pick one
+    // that is free in both directories.
+    let trash_name = first_free_name(&filename, |name| {
+        trash::path_exists(&trash_dir_files.join(name))
+            || trash::path_exists(&trash_dir_metadata.join(format!("{}.trashinfo", name)))
+    });
+    let trash_path = trash_dir_files.join(&trash_name);
+    let info_path = trash_dir_metadata.join(format!("{}.trashinfo", trash_name));
+
+    create_metadata_file(&info_path, path_str)?;
 
-    Ok(())
+    // Try to rename (move) the file to the trash directory
+    if let Err(e) = fs::rename(source, &trash_path) {
+        let _ = fs::remove_file(&info_path);
+        return Err(e);
+    }
+
+    Ok(trash_path)
 }
 
 /// Create a text file with metadata about the file being sent to the trash.
 /// Metadata includes the original path of the file, as well as the time and
 /// date it was moved to the trash.
-fn create_metadata_file(filename: &str) -> io::Result<()> {
+fn create_metadata_file(info_path: &Path, original_path: &str) -> io::Result<()> {
     let current_date_time = Local::now();
-    let formatted_date_time = current_date_time.format("%Y-%m-%dT%H:%M:%S").to_string();
+    let formatted_date_time = current_date_time.format(trash::DATE_FORMAT).to_string();
 
-    let trash_dir_metadata = dirs_next::home_dir()
-        .map(|home| home.join(".local/share/Trash/info"))
-        .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "Could not determine home directory"))?;
-
-    fs::create_dir_all(&trash_dir_metadata)?; // Ensure the directory exists
-
-    let new_filename = Path::new(filename)
-        .file_name()
-        .and_then(|s| s.to_str())
-        .map(|s| format!("{}.trashinfo", s))
-        .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "Invalid UTF-8 in filename"))?;
-
-    let final_filename = resolve_naming_conflict(&trash_dir_metadata, &new_filename);
-    let full_path = trash_dir_metadata.join(&final_filename);
-
-    let mut file = File::create(&full_path)?;
+    let mut file = File::create(info_path)?;
     writeln!(file, "[Trash Info]")?;
-    writeln!(file, "Path={}", filename)?;
+    // The spec wants the path percent-encoded, as read_item decodes it.
+    writeln!(file, "Path={}", trash::encode_path(original_path))?;
     writeln!(file, "DeletionDate={}", formatted_date_time)?;
 
-    // File is already in place, no need to move/rename
     Ok(())
 }
 
 /// Given a target directory and desired filename, append suffixes like (1), (2), etc if needed
 /// in order to resolve naming conflicts.
-fn resolve_naming_conflict(trash_dir: &Path, filename: &str) -> PathBuf {
-    let mut candidate = trash_dir.join(filename);
+pub fn resolve_naming_conflict(trash_dir: &Path, filename: &str) -> PathBuf {
+    trash_dir.join(first_free_name(filename, |name| {
+        trash::path_exists(&trash_dir.join(name))
+    }))
+}
+
+/// Returns `filename`, or the first of name(1).ext, name(2).ext, etc. for which
+/// `is_taken` returns false.
+fn first_free_name(filename: &str, is_taken: impl Fn(&str) -> bool) -> String {
+    let path = Path::new(filename);
+    let mut candidate = filename.to_string();
     let mut counter = 1;
 
-    while candidate.exists() {
-        let path = Path::new(filename);
-        let new_filename = format!(
+    while is_taken(&candidate) {
+        candidate = format!(
             "{}({}){}",
             path.file_stem().unwrap_or_default().to_string_lossy(),
             counter,
             path.extension()
                 .map_or(String::new(), |ext| format!(".{}", ext.to_string_lossy()))
         );
-        candidate = trash_dir.join(new_filename);
         counter += 1;
     }
 
@@ -159,7 +159,7 @@ fn resolve_naming_conflict(trash_dir: &Path, filename: &str) -> PathBuf {
 
 /// Prompts the user for a yes/no confirmation.
 /// Returns true if the user enters 'y' or 'Y'.
-fn confirm(prompt: &str) -> io::Result<bool> {
+pub fn confirm(prompt: &str) -> io::Result<bool> {
     print!("{} [y/N]: ", prompt);
 
     // Make sure prompt shows up before input
@@ -0,0 +1,66 @@
+///
+/// =====================================================================
+/// Project Name: rust rm
+/// Description: An enhanced version of the common rm utility.
+/// Author: Robert Pellegrin
+/// Date: 2025-06-26
+/// Version: 0.0.1
+/// License: MIT
+/// Repository:
+/// =====================================================================
+///
+/// Builds the commands the trash TUI runs to look inside a trashed file
+/// without restoring it: $PAGER, or $EDITOR in its read-only mode.
+///
+use std::{env, path::Path, process::Command};
+
+const DEFAULT_PAGER: &str = "less";
+const DEFAULT_EDITOR: &str = "vi";
+
+/// $PAGER showing `path`.
+pub fn pager(path: &Path) -> Option<Command> {
+    let mut command = from_env("PAGER", DEFAULT_PAGER)?;
+    command.arg(path);
+    Some(command)
+}
+
+/// $EDITOR opening `path` read-only, for the editors known to have such a
+/// mode. Others open it as usual.
+pub fn editor(path: &Path) -> Option<Command> {
+    let mut command = from_env("VISUAL", "").or_else(|| from_env("EDITOR", DEFAULT_EDITOR))?;
+    let program = Path::new(command.get_program())
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    match program.as_str() {
+        "vi" | "vim" | "nvim" | "gvim" | "view" => {
+            command.arg("-R");
+        }
+        "nano" => {
+            command.arg("--view");
+        }
+        _ => {}
+    }
+    command.arg(path);
+    Some(command)
+}
+
+/// Command from an environment variable such as "less -R", split on
+/// whitespace, or `default` if it is unset. None if both are empty.
+fn from_env(var: &str, default: &str) -> Option<Command> {
+    let value = env::var(var).unwrap_or_default();
+    let value = if value.trim().is_empty() {
+        default
+    } else {
+        &value
+    };
+    let mut words = value.split_whitespace();
+    let mut command = Command::new(words.next()?);
+    command.args(words);
+    Some(command)
+}
+
+/// The program a command runs, for error messages.
+pub fn program(command: &Command) -> String {
+    command.get_program().to_string_lossy().into_owned()
+}
@@ -0,0 +1,200 @@
+///
+/// =====================================================================
+/// Project Name: rust rm
+/// Description: An enhanced version of the common rm utility.
+/// Author: Robert Pellegrin
+/// Date: 2025-06-18
+/// Version: 0.0.1
+/// License: MIT
+/// Repository:
+/// =====================================================================
+///
+/// Builds the text shown in the trash TUI's preview pane: the head of a text
+/// file, a tree listing of a directory, or stat info for anything else.
+///
+use chrono::{DateTime, Local};
+use std::{
+    fs::{self, File, Metadata},
+    io::{self, Read},
+    os::unix::fs::{FileTypeExt, MetadataExt},
+    path::Path,
+};
+
+use crate::trash;
+
+/// Longest preview, in lines, for both files and directory trees.
+const MAX_LINES: usize = 200;
+
+/// Bytes read from a file to preview it and to decide whether it is binary.
+const HEAD_BYTES: usize = 64 * 1024;
+
+/// Returns the preview of `path`. Directories are expanded `depth` levels
+/// deep.
+pub fn preview(path: &Path, depth: usize) -> Vec<String> {
+    let meta = match fs::symlink_metadata(path) {
+        Ok(meta) => meta,
+        Err(e) => return vec![format!("cannot read: {}", e)],
+    };
+
+    let result = if meta.is_dir() {
+        Ok(Some(tree(path, depth)))
+    } else if meta.is_file() {
+        text_head(path)
+    } else {
+        Ok(None)
+    };
+
+    match result {
+        Ok(Some(lines)) => lines,
+        Ok(None) => stat(path, &meta),
+        Err(e) => vec![format!("cannot read: {}", e)],
+    }
+}
+
+/// First lines of a text file, or None if it looks binary.
+fn text_head(path: &Path) -> io::Result<Option<Vec<String>>> {
+    let mut buf = Vec::with_capacity(HEAD_BYTES);
+    File::open(path)?
+        .take(HEAD_BYTES as u64)
+        .read_to_end(&mut buf)?;
+
+    // Same heuristic as grep and git: a NUL byte means binary.
+    if buf.contains(&0) {
+        return Ok(None);
+    }
+    if buf.is_empty() {
+        return Ok(Some(vec!["(empty file)".to_string()]));
+    }
+
+    let text = String::from_utf8_lossy(&buf);
+    Ok(Some(
+        text.lines()
+            .take(MAX_LINES)
+            .map(|line| line.replace('\t', "    "))
+            .collect(),
+    ))
+}
+
+/// Tree listing of a directory, `depth` levels deep. Directories that are
+/// not expanded show their entry count instead.
+fn tree(path: &Path, depth: usize) -> Vec<String> {
+    let mut lines = vec![format!(
+        "{}/",
+        path.file_name().unwrap_or_default().to_string_lossy()
+    )];
+    tree_into(path, "", depth, &mut lines);
+    if lines.len() > MAX_LINES {
+        lines.truncate(MAX_LINES);
+        lines.push("…".to_string());
+    }
+    lines
+}
+
+fn tree_into(dir: &Path, prefix: &str, depth: usize, lines: &mut Vec<String>) {
+    let mut entries: Vec<_> = match fs::read_dir(dir) {
+        Ok(entries) => entries.filter_map(Result::ok).collect(),
+        Err(e) => {
+            lines.push(format!("{}└── cannot read: {}", prefix, e));
+            return;
+        }
+    };
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for (i, entry) in entries.iter().enumerate() {
+        if lines.len() > MAX_LINES {
+            return;
+        }
+        let last = i + 1 == entries.len();
+        let branch = if last { "└── " } else { "├── " };
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = entry.path();
+
+        if !entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+            lines.push(format!("{}{}{}", prefix, branch, name));
+            continue;
+        }
+
+        if depth <= 1 {
+            let count = fs::read_dir(&path).map(Iterator::count).unwrap_or(0);
+            lines.push(format!("{}{}{}/ ({} entries)", prefix, branch, name, count));
+            continue;
+        }
+
+        lines.push(format!("{}{}{}/", prefix, branch, name));
+        let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+        tree_into(&path, &prefix, depth - 1, lines);
+    }
+}
+
+/// Size, mode, owner and modification time of `path`.
+fn stat(path: &Path, meta: &Metadata) -> Vec<String> {
+    let kind = match meta.file_type() {
+        t if t.is_symlink() => match fs::read_link(path) {
+            Ok(target) => format!("symbolic link to {}", target.display()),
+            Err(_) => "symbolic link".to_string(),
+        },
+        t if t.is_file() => "binary file".to_string(),
+        t if t.is_fifo() => "fifo".to_string(),
+        t if t.is_socket() => "socket".to_string(),
+        t if t.is_block_device() => "block device".to_string(),
+        t if t.is_char_device() => "character device".to_string(),
+        _ => "unknown".to_string(),
+    };
+    let modified = meta
+        .modified()
+        .map(|time| {
+            DateTime::<Local>::from(time)
+                .format(trash::DATE_FORMAT)
+                .to_string()
+        })
+        .unwrap_or_else(|_| "unknown".to_string());
+
+    vec![
+        format!("type:     {}", kind),
+        format!("size:     {}", trash::format_size(meta.len())),
+        format!(
+            "mode:     {:o} ({})",
+            meta.mode() & 0o7777,
+            permissions(meta.mode())
+        ),
+        format!("owner:    {}", owner(meta)),
+        format!("modified: {}", modified),
+    ]
+}
+
+/// Formats permission bits the way ls does, i.e. rw-r--r--
+pub fn permissions(mode: u32) -> String {
+    let mut text = String::with_capacity(9);
+    for shift in [6, 3, 0] {
+        let bits = mode >> shift;
+        text.push(if bits & 4 != 0 { 'r' } else { '-' });
+        text.push(if bits & 2 != 0 { 'w' } else { '-' });
+        text.push(if bits & 1 != 0 { 'x' } else { '-' });
+    }
+    text
+}
+
+/// Owner and group of a file, i.e. root:root
+pub fn owner(meta: &Metadata) -> String {
+    format!(
+        "{}:{}",
+        account_name("/etc/passwd", meta.uid()),
+        account_name("/etc/group", meta.gid())
+    )
+}
+
+/// Looks up the name for `id` in /etc/passwd or /etc/group, falling back to
+/// the number itself.
+fn account_name(db: &str, id: u32) -> String {
+    let id = id.to_string();
+    fs::read_to_string(db)
+        .ok()
+        .and_then(|text| {
+            text.lines().find_map(|line| {
+                let mut fields = line.split(':');
+                let name = fields.next()?;
+                (fields.nth(1)? == id).then(|| name.to_string())
+            })
+        })
+        .unwrap_or(id)
+}
@@ -0,0 +1,527 @@
+///
+/// =====================================================================
+/// Project Name: rust rm
+/// Description: An enhanced version of the common rm utility.
+/// Author: Robert Pellegrin
+/// Date: 2025-06-02
+/// Version: 0.0.1
+/// License: MIT
+/// Repository:
+/// =====================================================================
+///
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime};
+use clap::ValueEnum;
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, ErrorKind, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{
+    mv,
+    trash::{self, TrashItem},
+};
+
+/// What to do when the destination of a restored item already exists.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ConflictPolicy {
+    /// restore under a new name, i.e. file(1).txt
+    Rename,
+    /// move the existing file to the trash, then restore
+    Overwrite,
+    /// leave the item in the trash
+    Skip,
+    /// prompt for one of the above
+    #[default]
+    Ask,
+}
+
+impl ConflictPolicy {
+    /// Name as written on the command line, i.e. "rename"
+    pub fn name(self) -> &'static str {
+        match self {
+            ConflictPolicy::Rename => "rename",
+            ConflictPolicy::Overwrite => "overwrite",
+            ConflictPolicy::Skip => "skip",
+            ConflictPolicy::Ask => "ask",
+        }
+    }
+}
+
+/// Which of several trashed versions of the same path to restore.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersionSelect {
+    /// list the versions and prompt for one
+    Ask,
+    /// the most recently deleted version
+    Latest,
+    /// the Nth version, counting from 1 for the oldest
+    Nth(usize),
+}
+
+/// Restores whatever `target` refers to: a whole trash entry (see
+/// [`find_item`]) or a single path inside a trashed directory (see
+/// [`find_inner`]). With `dest_dir` set it is restored there instead of to its
+/// original location.
+pub fn restore_target(
+    target: &str,
+    select: VersionSelect,
+    dest_dir: Option<&Path>,
+    policy: ConflictPolicy,
+) -> io::Result<Option<PathBuf>> {
+    let item = match find_item(target, select) {
+        Ok(Some(item)) => item,
+        Ok(None) => return Ok(None),
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            let Some((item, relative)) = find_inner(target)? else {
+                return Err(e);
+            };
+            let dest = match dest_dir {
+                Some(dir) => dir.join(relative.file_name().unwrap_or_default()),
+                None => item.original_path.join(&relative),
+            };
+            return restore_inner(&item, &relative, &dest, policy);
+        }
+        Err(e) => return Err(e),
+    };
+
+    match dest_dir {
+        Some(dir) => restore_item_into(&item, dir, policy),
+        None => restore_item(&item, policy),
+    }
+}
+
+/// Finds the trash entry meant by `target`, which is either the path it was
+/// deleted from or its name inside files/. Returns None if the user declined
+/// to pick one of several versions.
+pub fn find_item(target: &str, select: VersionSelect) -> io::Result<Option<TrashItem>> {
+    let mut versions = versions_of(&trash::absolute_path(Path::new(target))?)?;
+    if versions.is_empty() {
+        // A name inside files/ is a single version.
+        let item = trash::read_item(target)?;
+        check_version(select, 1)?;
+        return Ok(Some(item));
+    }
+
+    let count = versions.len();
+    check_version(select, count)?;
+    let index = match select {
+        VersionSelect::Nth(n) => n - 1,
+        _ if count == 1 => 0,
+        VersionSelect::Latest => count - 1,
+        VersionSelect::Ask => {
+            print_versions(target, &versions);
+            match prompt_version(count)? {
+                Some(n) => n - 1,
+                None => return Ok(None),
+            }
+        }
+    };
+
+    Ok(Some(versions.swap_remove(index)))
+}
+
+/// Fails if `select` asks for a version past the `count` in the trash.
+fn check_version(select: VersionSelect, count: usize) -> io::Result<()> {
+    match select {
+        VersionSelect::Nth(n) if !(1..=count).contains(&n) => Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("there is no version {} ({} in the trash)", n, count),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Every trash entry that was deleted from `path`, oldest first.
+pub fn versions_of(path: &Path) -> io::Result<Vec<TrashItem>> {
+    let mut versions: Vec<TrashItem> = trash::list_items()?
+        .into_iter()
+        .filter(|item| item.original_path == path)
+        .collect();
+    versions.sort_by_cached_key(TrashItem::deletion_key);
+    Ok(versions)
+}
+
+/// Finds a path inside a trashed directory. `target` is either relative to
+/// files/, i.e. build-scripts/deploy.sh, or a path below the directory's
+/// original location. Returns the directory's entry and the path inside it.
+pub fn find_inner(target: &str) -> io::Result<Option<(TrashItem, PathBuf)>> {
+    let files_dir = trash::files_dir()?;
+    let target_path = Path::new(target);
+
+    let mut components = target_path.components();
+    if let Some(Component::Normal(first)) = components.next() {
+        let relative = components.as_path().to_path_buf();
+        if !relative.as_os_str().is_empty()
+            && trash::path_exists(&files_dir.join(first).join(&relative))
+            && let Ok(item) = trash::read_item(&first.to_string_lossy())
+        {
+            return Ok(Some((item, relative)));
+        }
+    }
+
+    // Prefer the most recent deletion if the directory was trashed repeatedly.
+    let absolute = trash::absolute_path(target_path)?;
+    let mut candidates: Vec<(TrashItem, PathBuf)> = trash::list_items()?
+        .into_iter()
+        .filter_map(|item| {
+            let relative = absolute
+                .strip_prefix(&item.original_path)
+                .ok()?
+                .to_path_buf();
+            let inside = !relative.as_os_str().is_empty()
+                && trash::path_exists(&files_dir.join(&item.name).join(&relative));
+            inside.then_some((item, relative))
+        })
+        .collect();
+    candidates.sort_by_cached_key(|(item, _)| item.deletion_key());
+
+    Ok(candidates.pop())
+}
+
+/// Moves `relative` out of the trashed directory `item` to `dest`, leaving the
+/// rest of the directory and its .trashinfo file in the trash.
+pub fn restore_inner(
+    item: &TrashItem,
+    relative: &Path,
+    dest: &Path,
+    policy: ConflictPolicy,
+) -> io::Result<Option<PathBuf>> {
+    let source = item.file_path()?.join(relative);
+
+    let Some(target) = resolve_target(dest, policy)? else {
+        return Ok(None);
+    };
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::rename(&source, &target)?;
+    trash::sync_directory_size(item);
+
+    Ok(Some(target))
+}
+
+fn print_versions(target: &str, versions: &[TrashItem]) {
+    println!("'{}' has {} versions in the trash:", target, versions.len());
+    for (i, item) in versions.iter().enumerate() {
+        let date = item
+            .deleted_at()
+            .map(|date| date.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "Unknown".to_string());
+        let size = item
+            .file_path()
+            .map(|path| trash::format_size(trash::disk_usage(&path)))
+            .unwrap_or_default();
+        println!("  {:>2}) {}  {:>10}  {}", i + 1, date, size, item.name);
+    }
+}
+
+/// Asks which of `count` versions to restore. Returns None on an empty or
+/// invalid answer.
+fn prompt_version(count: usize) -> io::Result<Option<usize>> {
+    print!("restore which version? [1-{}]: ", count);
+
+    // Make sure prompt shows up before input
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    Ok(input
+        .trim()
+        .parse()
+        .ok()
+        .filter(|n| (1..=count).contains(n)))
+}
+
+/// Parses the --restore-since argument. Accepts a time today (14:05 or
+/// 14:05:30), a date (2025-05-17) or both (2025-05-17 14:05 / 2025-05-17T14:05).
+pub fn parse_since(value: &str) -> Result<NaiveDateTime, String> {
+    let value = value.trim();
+    for format in ["%H:%M", "%H:%M:%S"] {
+        if let Ok(time) = NaiveTime::parse_from_str(value, format) {
+            return Ok(Local::now().date_naive().and_time(time));
+        }
+    }
+    for format in [
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        trash::DATE_FORMAT,
+    ] {
+        if let Ok(date_time) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(date_time);
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(NaiveTime::MIN));
+    }
+    Err(format!(
+        "'{}' is not a time (HH:MM), date (YYYY-MM-DD) or both",
+        value
+    ))
+}
+
+/// Every trash entry deleted after `since` from somewhere below `under`,
+/// newest first.
+pub fn deleted_since(since: NaiveDateTime, under: Option<&Path>) -> io::Result<Vec<TrashItem>> {
+    let mut items: Vec<TrashItem> = trash::list_items()?
+        .into_iter()
+        .filter(|item| under.is_none_or(|dir| item.original_path.starts_with(dir)))
+        .filter(|item| {
+            item.deleted_at()
+                .is_some_and(|deleted_at| deleted_at >= since)
+        })
+        .collect();
+    items.sort_by_cached_key(TrashItem::deletion_key);
+    items.reverse();
+    Ok(items)
+}
+
+/// Restores everything returned by [`deleted_since`] after showing a preview
+/// and a summary of conflicts. A path deleted more than once only gets its
+/// latest version back; older ones stay in the trash and count as skipped.
+/// With `dry_run` only the preview is shown.
+pub fn restore_since(
+    since: NaiveDateTime,
+    under: Option<&Path>,
+    policy: ConflictPolicy,
+    dry_run: bool,
+    verbose: bool,
+) -> io::Result<()> {
+    let items = deleted_since(since, under)?;
+    let location = under.map_or(String::new(), |dir| format!(" under {}", dir.display()));
+
+    if items.is_empty() {
+        println!("Nothing was deleted since {}{}", since, location);
+        return Ok(());
+    }
+
+    println!(
+        "{} item(s) deleted since {}{}:",
+        items.len(),
+        since,
+        location
+    );
+
+    // Items are newest first, so the first one seen for a path is its latest
+    // version. Restoring the others too would only have them conflict with it.
+    let mut seen = HashSet::new();
+    let latest: Vec<bool> = items
+        .iter()
+        .map(|item| seen.insert(&item.original_path))
+        .collect();
+    let older = latest.iter().filter(|&&latest| !latest).count();
+
+    let mut existing = 0;
+    for (item, &latest) in items.iter().zip(&latest) {
+        let note = if !latest {
+            "  (older version, stays in the trash)"
+        } else if trash::path_exists(&item.original_path) {
+            existing += 1;
+            "  (already exists)"
+        } else {
+            ""
+        };
+        println!(
+            "  {}  {}{}",
+            item.deletion_date,
+            item.original_path.display(),
+            note
+        );
+    }
+
+    if existing > 0 {
+        println!(
+            "{} already exist (--on-conflict {})",
+            existing,
+            policy.name(),
+        );
+    }
+    if older > 0 {
+        println!(
+            "{} older version(s) of paths deleted more than once stay in the trash",
+            older
+        );
+    }
+
+    if dry_run || !mv::confirm(&format!("Restore {} item(s)?", items.len() - older))? {
+        return Ok(());
+    }
+
+    let (mut restored, mut skipped, mut failed) = (0, older, 0);
+    for (item, _) in items.iter().zip(&latest).filter(|(_, latest)| **latest) {
+        match restore_item(item, policy) {
+            Ok(Some(target)) => {
+                restored += 1;
+                if verbose {
+                    println!("restored '{}'", target.display());
+                }
+            }
+            Ok(None) => skipped += 1,
+            Err(e) => {
+                failed += 1;
+                eprintln!(
+                    "rrm: cannot restore '{}': {}",
+                    item.original_path.display(),
+                    e
+                );
+            }
+        }
+    }
+
+    println!(
+        "{} restored, {} skipped, {} failed",
+        restored, skipped, failed
+    );
+    Ok(())
+}
+
+/// Moves a trashed item back to its original location and removes its
+/// .trashinfo file. Returns where the item ended up, or None if it was skipped.
+pub fn restore_item(item: &TrashItem, policy: ConflictPolicy) -> io::Result<Option<PathBuf>> {
+    restore_item_to(item, &item.original_path, policy)
+}
+
+/// Moves a trashed item into `dir` under its original basename and removes its
+/// .trashinfo file.
+pub fn restore_item_into(
+    item: &TrashItem,
+    dir: &Path,
+    policy: ConflictPolicy,
+) -> io::Result<Option<PathBuf>> {
+    restore_item_to(item, &dir.join(item.original_name()), policy)
+}
+
+/// Moves a trashed item to `dest` and removes its .trashinfo file.
+pub fn restore_item_to(
+    item: &TrashItem,
+    dest: &Path,
+    policy: ConflictPolicy,
+) -> io::Result<Option<PathBuf>> {
+    let source = item.file_path()?;
+    if !trash::path_exists(&source) {
+        return Err(io::Error::new(
+            ErrorKind::NotFound,
+            format!("'{}' is missing from the trash", item.name),
+        ));
+    }
+
+    let Some(target) = resolve_target(dest, policy)? else {
+        return Ok(None);
+    };
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::rename(&source, &target)?;
+    trash::remove_info(item)?;
+    trash::sync_directory_size(item);
+
+    Ok(Some(target))
+}
+
+/// Applies `policy` if `dest` is already taken. Returns the path the item
+/// should be restored to, or None if it should stay in the trash.
+pub fn resolve_target(dest: &Path, policy: ConflictPolicy) -> io::Result<Option<PathBuf>> {
+    if !trash::path_exists(dest) {
+        return Ok(Some(dest.to_path_buf()));
+    }
+
+    match policy {
+        ConflictPolicy::Rename => {
+            let parent = dest.parent().unwrap_or(Path::new("/"));
+            let filename = dest.file_name().unwrap_or_default().to_string_lossy();
+            Ok(Some(mv::resolve_naming_conflict(parent, &filename)))
+        }
+        ConflictPolicy::Overwrite => {
+            // Trash whatever is in the way so that nothing is lost.
+            mv::trash(dest)?;
+            Ok(Some(dest.to_path_buf()))
+        }
+        ConflictPolicy::Skip => Ok(None),
+        ConflictPolicy::Ask => resolve_target(dest, prompt_policy(dest)?),
+    }
+}
+
+/// Asks the user how to handle an existing file at `dest`.
+fn prompt_policy(dest: &Path) -> io::Result<ConflictPolicy> {
+    print!(
+        "'{}' already exists. [r]ename, [o]verwrite, [s]kip? ",
+        dest.display()
+    );
+
+    // Make sure prompt shows up before input
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    Ok(match input.trim().to_lowercase().as_str() {
+        "r" | "rename" => ConflictPolicy::Rename,
+        "o" | "overwrite" => ConflictPolicy::Overwrite,
+        _ => ConflictPolicy::Skip,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date_time(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, trash::DATE_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn parse_since_takes_a_time_today() {
+        let today = Local::now().date_naive();
+        assert_eq!(
+            parse_since("14:05"),
+            Ok(today.and_hms_opt(14, 5, 0).unwrap())
+        );
+        assert_eq!(
+            parse_since(" 14:05:30 "),
+            Ok(today.and_hms_opt(14, 5, 30).unwrap())
+        );
+    }
+
+    #[test]
+    fn parse_since_takes_dates() {
+        assert_eq!(
+            parse_since("2025-05-17"),
+            Ok(date_time("2025-05-17T00:00:00"))
+        );
+        assert_eq!(
+            parse_since("2025-05-17 14:05"),
+            Ok(date_time("2025-05-17T14:05:00"))
+        );
+        assert_eq!(
+            parse_since("2025-05-17T14:05"),
+            Ok(date_time("2025-05-17T14:05:00"))
+        );
+        assert_eq!(
+            parse_since("2025-05-17T14:05:30"),
+            Ok(date_time("2025-05-17T14:05:30"))
+        );
+    }
+
+    #[test]
+    fn check_version_rejects_missing_versions() {
+        assert!(check_version(VersionSelect::Nth(1), 1).is_ok());
+        assert!(check_version(VersionSelect::Nth(3), 3).is_ok());
+        assert!(check_version(VersionSelect::Nth(7), 1).is_err());
+        assert!(check_version(VersionSelect::Nth(0), 2).is_err());
+        assert!(check_version(VersionSelect::Latest, 1).is_ok());
+    }
+
+    #[test]
+    fn parse_since_rejects_other_input() {
+        for value in ["", "yesterday", "25:00", "2025-13-01", "14:05 2025-05-17"] {
+            assert!(parse_since(value).is_err(), "{}", value);
+        }
+    }
+}
@@ -0,0 +1,418 @@
+///
+/// =====================================================================
+/// Project Name: rust rm
+/// Description: An enhanced version of the common rm utility.
+/// Author: Robert Pellegrin
+/// Date: 2025-06-02
+/// Version: 0.0.1
+/// License: MIT
+/// Repository:
+/// =====================================================================
+///
+use chrono::NaiveDateTime;
+use std::{
+    ffi::OsStr,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, ErrorKind},
+    path::{Component, Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Format of the DeletionDate= value in .trashinfo files.
+pub const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Returns the base trash directory, ~/.local/share/Trash
+pub fn trash_dir() -> io::Result<PathBuf> {
+    dirs_next::home_dir()
+        .map(|home| home.join(".local/share/Trash"))
+        .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "Could not determine home directory"))
+}
+
+/// Returns the directory holding the trashed files themselves.
+pub fn files_dir() -> io::Result<PathBuf> {
+    Ok(trash_dir()?.join("files"))
+}
+
+/// Returns the directory holding the .trashinfo metadata files.
+pub fn info_dir() -> io::Result<PathBuf> {
+    Ok(trash_dir()?.join("info"))
+}
+
+/// A single entry in the trash, i.e. a file in files/ paired with its
+/// .trashinfo file in info/.
+pub struct TrashItem {
+    /// Name of the entry inside files/
+    pub name: String,
+    /// Decoded original location of the entry
+    pub original_path: PathBuf,
+    /// Raw DeletionDate value, i.e. 2025-05-17T12:00:00
+    pub deletion_date: String,
+}
+
+impl TrashItem {
+    /// Basename of the original location, i.e. config.yaml rather than the
+    /// config(2).yaml it may be stored as.
+    pub fn original_name(&self) -> &OsStr {
+        self.original_path
+            .file_name()
+            .unwrap_or(OsStr::new(&self.name))
+    }
+
+    /// Parsed DeletionDate, or None if it is missing or malformed.
+    pub fn deleted_at(&self) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse_from_str(&self.deletion_date, DATE_FORMAT).ok()
+    }
+
+    /// Sort key putting entries in the order they were deleted. DeletionDate
+    /// only has whole seconds, so ties fall back to when the .trashinfo was
+    /// written, then to the (N) the name got to stay unique.
+    pub fn deletion_key(&self) -> (Option<NaiveDateTime>, Option<SystemTime>, u32, String) {
+        let written = self
+            .info_path()
+            .and_then(fs::metadata)
+            .and_then(|meta| meta.modified())
+            .ok();
+        (
+            self.deleted_at(),
+            written,
+            name_counter(&self.name),
+            self.name.clone(),
+        )
+    }
+
+    /// Location of the trashed file or directory inside files/
+    pub fn file_path(&self) -> io::Result<PathBuf> {
+        Ok(files_dir()?.join(&self.name))
+    }
+
+    /// Location of the matching .trashinfo file inside info/
+    pub fn info_path(&self) -> io::Result<PathBuf> {
+        Ok(info_dir()?.join(format!("{}.trashinfo", self.name)))
+    }
+}
+
+/// The N of a name made unique as name(N).ext, or 0 for other names.
+fn name_counter(name: &str) -> u32 {
+    let stem = Path::new(name)
+        .file_stem()
+        .map_or(name.into(), OsStr::to_string_lossy);
+    stem.strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once('('))
+        .and_then(|(_, n)| n.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Reads the .trashinfo file for the entry called `name` in files/.
+pub fn read_item(name: &str) -> io::Result<TrashItem> {
+    let info_path = info_dir()?.join(format!("{}.trashinfo", name));
+    let reader = BufReader::new(File::open(&info_path)?);
+
+    let mut original_path = None;
+    let mut deletion_date = None;
+
+    for line in reader.lines() {
+        let line = line?;
+        if let Some(value) = line.strip_prefix("Path=") {
+            original_path = Some(PathBuf::from(decode_path(value.trim())));
+        } else if let Some(value) = line.strip_prefix("DeletionDate=") {
+            deletion_date = Some(value.trim().to_string());
+        }
+    }
+
+    let original_path = original_path.ok_or_else(|| {
+        io::Error::new(
+            ErrorKind::InvalidData,
+            format!("{} has no Path= entry", info_path.display()),
+        )
+    })?;
+
+    Ok(TrashItem {
+        name: name.to_string(),
+        original_path,
+        deletion_date: deletion_date.unwrap_or_default(),
+    })
+}
+
+/// Reads every entry in the trash that has a valid .trashinfo file.
+pub fn list_items() -> io::Result<Vec<TrashItem>> {
+    let files_dir = files_dir()?;
+    if !files_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let items = fs::read_dir(&files_dir)?
+        .flatten()
+        .filter_map(|entry| read_item(&entry.file_name().to_string_lossy()).ok())
+        .collect();
+
+    Ok(items)
+}
+
+/// Removes the .trashinfo file belonging to an entry that has left files/.
+pub fn remove_info(item: &TrashItem) -> io::Result<()> {
+    match fs::remove_file(item.info_path()?) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Keeps the directorysizes cache in step with a trashed directory whose
+/// contents changed. The entry's line gets its size recomputed, or is dropped
+/// if the entry has left the trash. Does nothing if there is no cache.
+pub fn sync_directory_size(item: &TrashItem) {
+    // The cache is only advisory, so a stale line is not worth failing over.
+    let _ = write_directory_size(item);
+}
+
+fn write_directory_size(item: &TrashItem) -> io::Result<()> {
+    let cache_path = trash_dir()?.join("directorysizes");
+    let Ok(contents) = fs::read_to_string(&cache_path) else {
+        return Ok(());
+    };
+
+    let encoded_name = encode_path(&item.name);
+    let file_path = item.file_path()?;
+    let info_mtime = fs::metadata(item.info_path()?)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|mtime| mtime.duration_since(UNIX_EPOCH).ok())
+        .map(|mtime| mtime.as_secs());
+
+    let mut updated = String::with_capacity(contents.len());
+    for line in contents.lines() {
+        // Each line is "<size> <info mtime> <percent-encoded name>"
+        if line.splitn(3, ' ').nth(2) != Some(encoded_name.as_str()) {
+            updated.push_str(line);
+            updated.push('\n');
+        } else if let (true, Some(mtime)) = (path_exists(&file_path), info_mtime) {
+            updated.push_str(&format!(
+                "{} {} {}\n",
+                disk_usage(&file_path),
+                mtime,
+                encoded_name
+            ));
+        }
+    }
+
+    // Write a temporary file and rename it over the cache so readers never
+    // see a partial file.
+    let temp_path = trash_dir()?.join(format!("directorysizes.{}", std::process::id()));
+    fs::write(&temp_path, updated)?;
+    fs::rename(&temp_path, &cache_path)
+}
+
+/// Percent-encodes a path for the Path= line of a .trashinfo file or a
+/// name for the directorysizes cache.
+pub fn encode_path(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Decodes the percent-encoded Path= value of a .trashinfo file. Sequences
+/// that are not valid escapes are kept as-is.
+pub fn decode_path(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        // from_str_radix would also take a sign, as in %+1.
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1..i + 3].iter().all(u8::is_ascii_hexdigit)
+        {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(byte) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Expands a leading ~ to the user's home directory.
+pub fn expand_tilde(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), dirs_next::home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            home.join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Turns `path` into an absolute path the way it would have been recorded in
+/// Path=, i.e. with the parent directory canonicalized and `.`/`..` removed.
+pub fn absolute_path(path: &Path) -> io::Result<PathBuf> {
+    let mut absolute = PathBuf::new();
+    for component in std::path::absolute(path)?.components() {
+        match component {
+            Component::ParentDir => {
+                absolute.pop();
+            }
+            Component::CurDir => {}
+            other => absolute.push(other),
+        }
+    }
+
+    if let (Some(parent), Some(name)) = (absolute.parent(), absolute.file_name())
+        && let Ok(parent) = fs::canonicalize(parent)
+    {
+        return Ok(parent.join(name));
+    }
+    Ok(absolute)
+}
+
+/// Total size in bytes of a file, or of everything below a directory.
+pub fn disk_usage(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| disk_usage(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Number of inodes a file or directory tree uses, counting the directory
+/// itself.
+pub fn inode_count(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return 1;
+    }
+
+    1 + fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| inode_count(&entry.path()))
+                .sum::<u64>()
+        })
+        .unwrap_or(0)
+}
+
+/// Formats how long before `now` `date` was, i.e. 3 h ago or yesterday.
+pub fn format_age(date: NaiveDateTime, now: NaiveDateTime) -> String {
+    let seconds = (now - date).num_seconds();
+    let days = (now.date() - date.date()).num_days();
+    let (count, unit) = match seconds {
+        ..0 => return "in the future".to_string(),
+        0..60 => return "just now".to_string(),
+        60..3600 => return format!("{} min ago", seconds / 60),
+        3600..86400 => return format!("{} h ago", seconds / 3600),
+        _ if days == 1 => return "yesterday".to_string(),
+        _ if days < 7 => (days, "day"),
+        _ if days < 30 => (days / 7, "week"),
+        _ if days < 365 => (days / 30, "month"),
+        _ => (days / 365, "year"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    format!("{} {}{} ago", count, unit, plural)
+}
+
+/// Formats a byte count for display, i.e. 1.2 KiB
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    #[allow(clippy::cast_precision_loss)]
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Returns true if `path` exists, without following a trailing symlink.
+pub fn path_exists(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_path_decodes_escapes() {
+        assert_eq!(decode_path("/home/me/a%20b"), "/home/me/a b");
+        assert_eq!(decode_path("/tmp/%C3%A9t%C3%A9"), "/tmp/été");
+        assert_eq!(decode_path("/tmp/x%2541y"), "/tmp/x%41y");
+    }
+
+    #[test]
+    fn decode_path_keeps_invalid_escapes() {
+        assert_eq!(decode_path("/tmp/100%"), "/tmp/100%");
+        assert_eq!(decode_path("/tmp/%4"), "/tmp/%4");
+        assert_eq!(decode_path("/tmp/%zz"), "/tmp/%zz");
+        assert_eq!(decode_path("/tmp/%+1"), "/tmp/%+1");
+    }
+
+    #[test]
+    fn encode_path_round_trips() {
+        for path in ["/tmp/x%41y", "/home/me/a b", "/tmp/été", "/tmp/[1]#?.txt"] {
+            assert_eq!(decode_path(&encode_path(path)), path);
+        }
+        assert_eq!(encode_path("/home/me/a b"), "/home/me/a%20b");
+    }
+
+    #[test]
+    fn name_counter_reads_the_unique_suffix() {
+        assert_eq!(name_counter("config.yaml"), 0);
+        assert_eq!(name_counter("config(2).yaml"), 2);
+        assert_eq!(name_counter("config(12)"), 12);
+        assert_eq!(name_counter("archive.tar(1).gz"), 1);
+        assert_eq!(name_counter("notes(draft).txt"), 0);
+    }
+
+    #[test]
+    fn same_second_deletions_sort_by_suffix() {
+        let item = |name: &str| TrashItem {
+            name: name.to_string(),
+            original_path: PathBuf::from("/tmp/rrm-test/config.yaml"),
+            deletion_date: "2025-05-17T12:00:00".to_string(),
+        };
+        let mut items = [
+            item("rrm-test-config(10).yaml"),
+            item("rrm-test-config.yaml"),
+            item("rrm-test-config(2).yaml"),
+        ];
+        items.sort_by_cached_key(TrashItem::deletion_key);
+        let names: Vec<&str> = items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "rrm-test-config.yaml",
+                "rrm-test-config(2).yaml",
+                "rrm-test-config(10).yaml"
+            ]
+        );
+    }
+}
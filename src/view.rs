@@ -10,25 +10,55 @@
 /// =====================================================================
 ///
 use std::{
-    fs::{self, File},
-    io::{BufRead, BufReader},
+    borrow::Cow,
+    cmp::Ordering,
+    collections::{HashMap, HashSet, VecDeque},
+    env, fs, io,
+    os::unix::fs::MetadataExt,
+    panic,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::{Duration, Instant},
 };
 
+use chrono::{Local, Locale, NaiveDateTime, TimeZone};
 use color_eyre::Result;
 use ratatui::{
     DefaultTerminal, Frame,
-    crossterm::event::{self, Event, KeyCode, KeyEventKind},
-    layout::{Constraint, Layout, Margin, Rect},
+    crossterm::{
+        event::{
+            self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind,
+            MouseButton, MouseEvent, MouseEventKind,
+        },
+        execute,
+        terminal::{self, EnterAlternateScreen},
+    },
+    layout::{Constraint, Flex, Layout, Margin, Rect},
     style::{self, Color, Modifier, Style, Stylize},
-    text::Text,
+    text::{Line, Span, Text},
     widgets::{
-        Block, BorderType, Cell, HighlightSpacing, Paragraph, Row, Scrollbar, ScrollbarOrientation,
-        ScrollbarState, Table, TableState,
+        Block, BorderType, Cell, Clear, HighlightSpacing, Paragraph, Row, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, Table, TableState, Wrap,
     },
 };
+use rayon::prelude::*;
 
 use style::palette::tailwind;
-use unicode_width::UnicodeWidthStr;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::{
+    clipboard,
+    config::{self, Config},
+    diff::{self, DiffLine},
+    empty, fuzzy,
+    keymap::{Action, Keymap},
+    open, preview,
+    restore::{self, ConflictPolicy},
+    trash,
+    watch::TrashWatcher,
+};
 
 const PALETTES: [tailwind::Palette; 4] = [
     tailwind::BLUE,
@@ -36,12 +66,51 @@ const PALETTES: [tailwind::Palette; 4] = [
     tailwind::INDIGO,
     tailwind::RED,
 ];
-const INFO_TEXT: [&str; 2] = [
-    "(Esc) quit | (↑) move up | (↓) move down | (←) move left | (→) move right",
-    "(d) delete | (r) restore | (e) empty trash",
+/// Actions listed in each line of the footer. The rest are in the help.
+const FOOTER: [&[Action]; 2] = [
+    &[
+        Action::Quit,
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Search,
+        Action::Open,
+        Action::Help,
+    ],
+    &[
+        Action::Mark,
+        Action::Visual,
+        Action::Restore,
+        Action::RestoreTo,
+        Action::Delete,
+        Action::Preview,
+        Action::Info,
+        Action::Group,
+        Action::NextSort,
+    ],
 ];
 
+/// How long to wait for a key before checking the trash for changes.
+const REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+/// How often the table is redrawn while the trash is loading.
+const LOADING_INTERVAL: Duration = Duration::from_millis(50);
+/// Entries read per batch by the background loader.
+const LOAD_BATCH: usize = 512;
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
 const ITEM_HEIGHT: usize = 4;
+/// Narrowest the name column gets before long names lose their middle.
+const MIN_NAME_WIDTH: u16 = 12;
+/// Narrowest the path column gets before the date and size are hidden.
+const MIN_PATH_WIDTH: u16 = 16;
+/// Chars a cut off cell moves per scroll.
+const SCROLL_STEP: usize = 4;
+/// How long the result of an action stays in the status line.
+const STATUS_TIMEOUT: Duration = Duration::from_secs(4);
+/// Errors stay longer so there is time to read them.
+const ERROR_TIMEOUT: Duration = Duration::from_secs(8);
+/// Longest gap between the clicks of a double click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
 
 struct TableColors {
     buffer_bg: Color,
@@ -54,6 +123,15 @@ struct TableColors {
     normal_row_color: Color,
     alt_row_color: Color,
     footer_border_color: Color,
+    match_fg: Color,
+    marked_fg: Color,
+    age_warn_fg: Color,
+    age_old_fg: Color,
+    error_fg: Color,
+    error_bg: Color,
+    hunk_fg: Color,
+    added_fg: Color,
+    removed_fg: Color,
 }
 
 impl TableColors {
@@ -69,6 +147,15 @@ impl TableColors {
             normal_row_color: tailwind::SLATE.c950,
             alt_row_color: tailwind::SLATE.c900,
             footer_border_color: color.c400,
+            match_fg: tailwind::AMBER.c400,
+            marked_fg: color.c300,
+            age_warn_fg: tailwind::ORANGE.c300,
+            age_old_fg: tailwind::RED.c400,
+            error_fg: tailwind::SLATE.c50,
+            error_bg: tailwind::RED.c700,
+            hunk_fg: tailwind::CYAN.c400,
+            added_fg: tailwind::GREEN.c400,
+            removed_fg: tailwind::RED.c400,
         }
     }
 }
@@ -77,11 +164,34 @@ struct TrashEntry {
     file: String,
     path: String,
     date: String,
+    deleted_at: Option<NaiveDateTime>,
+    size: u64,
+    size_text: String,
 }
 
 impl TrashEntry {
-    const fn ref_array(&self) -> [&String; 3] {
-        [&self.file, &self.path, &self.date]
+    /// Reads the entry called `file_name` in files/ along with its .trashinfo.
+    fn load(file_name: String) -> Self {
+        // Parse the .trashinfo once for both fields.
+        let (original_path, date_info) = match trash::read_item(&file_name) {
+            Ok(item) if !item.deletion_date.is_empty() => {
+                (tilde(&item.original_path), item.deletion_date)
+            }
+            Ok(item) => (tilde(&item.original_path), "Unknown".to_string()),
+            Err(_) => ("Unknown".to_string(), "Unknown".to_string()),
+        };
+        let size = trash::files_dir()
+            .map(|dir| trash::disk_usage(&dir.join(&file_name)))
+            .unwrap_or(0);
+
+        Self {
+            deleted_at: NaiveDateTime::parse_from_str(&date_info, trash::DATE_FORMAT).ok(),
+            file: file_name,
+            path: original_path,
+            date: date_info,
+            size,
+            size_text: trash::format_size(size),
+        }
     }
 
     fn file(&self) -> &str {
@@ -92,37 +202,701 @@ impl TrashEntry {
         &self.path
     }
 
-    fn date(&self) -> &str {
-        &self.date
+    fn size_text(&self) -> &str {
+        &self.size_text
+    }
+}
+
+/// How the Date column shows deletion times.
+struct DateDisplay {
+    relative: bool, // "3 h ago" rather than the full date
+    format: String, // strftime format for full dates
+    locale: Option<Locale>,
+}
+
+impl DateDisplay {
+    /// Relative dates are never wider than "11 months ago".
+    const RELATIVE_WIDTH: u16 = 13;
+
+    fn new(format: Option<String>) -> Self {
+        let locale = time_locale();
+        // Without a locale, %x would fall back to the US format.
+        let format = format.unwrap_or_else(|| match locale {
+            Some(_) => "%x %X".to_string(),
+            None => "%Y-%m-%d %H:%M:%S".to_string(),
+        });
+        Self {
+            relative: true,
+            format,
+            locale,
+        }
+    }
+
+    /// Formats `date`, or shows `raw` if the date could not be parsed.
+    fn text(&self, date: Option<NaiveDateTime>, raw: &str, now: NaiveDateTime) -> String {
+        let Some(date) = date else {
+            return raw.to_string();
+        };
+        if self.relative {
+            return trash::format_age(date, now);
+        }
+        match (self.locale, Local.from_local_datetime(&date).earliest()) {
+            (Some(locale), Some(local)) => local.format_localized(&self.format, locale).to_string(),
+            _ => date.format(&self.format).to_string(),
+        }
+    }
+
+    fn width(&self) -> u16 {
+        if self.relative {
+            return Self::RELATIVE_WIDTH;
+        }
+        let sample = Local::now().naive_local();
+        #[allow(clippy::cast_possible_truncation)]
+        let width = self.text(Some(sample), "", sample).width() as u16;
+        width
+    }
+
+    /// Text color for entries old enough to be worth purging.
+    fn age_color(
+        &self,
+        date: Option<NaiveDateTime>,
+        now: NaiveDateTime,
+        colors: &TableColors,
+    ) -> Option<Color> {
+        match (now - date?).num_days() {
+            ..30 => None,
+            30..90 => Some(colors.age_warn_fg),
+            _ => Some(colors.age_old_fg),
+        }
+    }
+}
+
+/// The locale for dates, from LC_ALL, LC_TIME or LANG like libc does.
+fn time_locale() -> Option<Locale> {
+    let value = ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())?;
+    // de_DE.UTF-8@euro -> de_DE
+    let name = value.split(['.', '@']).next()?;
+    if name == "C" || name == "POSIX" {
+        return None;
+    }
+    Locale::try_from(name).ok()
+}
+
+/// Columns of the table, in display order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SortColumn {
+    Name,
+    Path,
+    Date,
+    Size,
+}
+
+impl SortColumn {
+    const ALL: [SortColumn; 4] = [
+        SortColumn::Name,
+        SortColumn::Path,
+        SortColumn::Date,
+        SortColumn::Size,
+    ];
+
+    const fn title(self) -> &'static str {
+        match self {
+            SortColumn::Name => "File Name",
+            SortColumn::Path => "Original Path",
+            SortColumn::Date => "Date",
+            SortColumn::Size => "Size",
+        }
+    }
+
+    fn compare(self, a: &TrashEntry, b: &TrashEntry) -> Ordering {
+        match self {
+            SortColumn::Name => a.file.cmp(&b.file),
+            SortColumn::Path => a.path.cmp(&b.path),
+            SortColumn::Date => a.deleted_at.cmp(&b.deleted_at),
+            SortColumn::Size => a.size.cmp(&b.size),
+        }
+    }
+}
+
+/// An action on several entries that needs confirming first.
+enum BulkAction {
+    Restore(Vec<String>),
+    Delete(Vec<String>),
+    Empty,
+}
+
+/// Diff of a trash entry against its original path, in a scrollable popup.
+struct DiffView {
+    name: String,
+    lines: Vec<DiffLine>,
+    scroll: usize, // first line shown
+    page: usize,   // lines that fit in the popup
+}
+
+/// Result of the last action, shown in the status line for a while.
+struct Status {
+    text: String,
+    error: bool,
+    shown_at: Instant,
+}
+
+/// A row of the table, pointing into `App::items`. Rows are a subset of the
+/// items while a search is active.
+struct VisibleRow {
+    item: usize,
+    score: i64,
+    file_hits: Vec<usize>, // char indexes in the file name matching the search
+    path_hits: Vec<usize>, // char indexes in the original path matching the search
+    group: Option<usize>,  // set on group headers, pointing into `App::groups`
+}
+
+/// Entries deleted from the same directory, shown under one header in
+/// grouped mode.
+struct Group {
+    dir: String,
+    names: Vec<String>,
+    size: u64,
+    latest: Option<NaiveDateTime>,
+}
+
+/// A file browser scoped to a trashed directory.
+struct Browser {
+    name: String,           // trash entry being browsed
+    original_path: PathBuf, // where the entry was deleted from
+    relative: PathBuf,      // current directory below the entry
+    entries: Vec<BrowseEntry>,
+    state: TableState,
+}
+
+struct BrowseEntry {
+    name: String,
+    is_dir: bool,
+    size_text: String,
+}
+
+impl Browser {
+    /// Opens the trash entry `name` if it is a directory.
+    fn open(name: &str) -> Option<Self> {
+        let item = trash::read_item(name).ok()?;
+        if !item.file_path().ok()?.is_dir() {
+            return None;
+        }
+        let mut browser = Self {
+            name: name.to_string(),
+            original_path: item.original_path,
+            relative: PathBuf::new(),
+            entries: Vec::new(),
+            state: TableState::default().with_selected(0),
+        };
+        browser.load();
+        Some(browser)
+    }
+
+    /// Rereads the current directory, directories first.
+    fn load(&mut self) {
+        let dir = trash::files_dir()
+            .map(|dir| dir.join(&self.name).join(&self.relative))
+            .and_then(fs::read_dir);
+        self.entries = dir
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| {
+                        let path = entry.path();
+                        BrowseEntry {
+                            name: entry.file_name().to_string_lossy().into_owned(),
+                            is_dir: entry.file_type().is_ok_and(|kind| kind.is_dir()),
+                            size_text: trash::format_size(trash::disk_usage(&path)),
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.entries
+            .sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+
+        let last = self.entries.len().saturating_sub(1);
+        self.state
+            .select(Some(self.state.selected().unwrap_or(0).min(last)));
+    }
+
+    fn selected(&self) -> Option<&BrowseEntry> {
+        self.entries.get(self.state.selected()?)
+    }
+
+    /// Path of the selected entry relative to files/.
+    fn selected_source(&self) -> Option<PathBuf> {
+        let entry = self.selected()?;
+        Some(Path::new(&self.name).join(&self.relative).join(&entry.name))
+    }
+
+    /// Where the selected entry would be restored to.
+    fn selected_dest(&self) -> Option<PathBuf> {
+        let entry = self.selected()?;
+        Some(self.original_path.join(&self.relative).join(&entry.name))
+    }
+
+    /// Descends into the selected entry if it is a directory.
+    fn enter(&mut self) {
+        if let Some(name) = self
+            .selected()
+            .filter(|entry| entry.is_dir)
+            .map(|entry| entry.name.clone())
+        {
+            self.relative.push(name);
+            self.state.select(Some(0));
+            self.load();
+        }
+    }
+
+    /// Goes up one level. Returns false if already at the top.
+    fn up(&mut self) -> bool {
+        let Some(left) = self
+            .relative
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+        else {
+            return false;
+        };
+        self.relative.pop();
+        self.load();
+        let row = self.entries.iter().position(|entry| entry.name == left);
+        self.state.select(row.or(Some(0)));
+        true
+    }
+
+    fn next_row(&mut self) {
+        if !self.entries.is_empty() {
+            let i = self
+                .state
+                .selected()
+                .map_or(0, |i| (i + 1) % self.entries.len());
+            self.state.select(Some(i));
+        }
+    }
+
+    fn previous_row(&mut self) {
+        if !self.entries.is_empty() {
+            let len = self.entries.len();
+            let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+            self.state.select(Some(i));
+        }
+    }
+
+    /// The original path followed by each directory below it.
+    fn breadcrumbs(&self) -> String {
+        let mut crumbs = vec![self.original_path.display().to_string()];
+        crumbs.extend(
+            self.relative
+                .iter()
+                .map(|part| part.to_string_lossy().into_owned()),
+        );
+        crumbs.join(" › ")
     }
 }
 
 struct App {
     state: TableState,
     items: Vec<TrashEntry>,
-    longest_item_lens: (u16, u16, u16), // order is (file, path, date)
+    visible: Vec<VisibleRow>,
+    search: String,
+    searching: bool,                    // true while the search line has focus
+    longest_item_lens: (u16, u16, u16), // order is (file, path, size)
+    dates: DateDisplay,
+    sort_column: SortColumn,
+    sort_descending: bool,
     scroll_state: ScrollbarState,
     colors: TableColors,
     color_index: usize,
+    on_conflict: ConflictPolicy,
+    pending_conflicts: VecDeque<(PathBuf, PathBuf)>, // sources (relative to files/) and destinations awaiting a choice
+    browser: Option<Browser>,
+    path_input: Option<(Vec<String>, String)>, // entries and typed destination directory
+    pending_action: Option<BulkAction>,        // action awaiting confirmation
+    marked: HashSet<String>,                   // names of marked entries
+    removed: HashSet<String>, // entries gone from the trash, dropped before the next draw
+    visual_anchor: Option<usize>, // row where the visual range starts
+    show_preview: bool,
+    details: Option<(String, Vec<String>)>, // entry and lines of the details popup
+    watcher: Option<TrashWatcher>,
+    keymap: Keymap,
+    help: bool,                                    // the help overlay is open
+    preview_depth: usize,                          // directory levels expanded in the preview
+    preview: Option<(String, usize, Vec<String>)>, // entry and depth it was built for
+    grouped: bool,                                 // rows are grouped by original parent directory
+    groups: Vec<Group>,                            // groups shown in grouped mode
+    collapsed: HashSet<String>,                    // directories of collapsed groups
+    row_offset: usize, // first row drawn, so only the rows on screen are built
+    loading: Option<Receiver<Vec<TrashEntry>>>, // batches from the background loader
+    reloaded: Option<Vec<TrashEntry>>, // entries replacing `items` once loading finishes
+    spinner: usize,
+    table_area: Rect,         // where the table was last drawn, for the mouse
+    columns: Vec<(u16, u16)>, // x and width of each column, 0 if hidden
+    h_scroll: usize,          // chars the selected cell is scrolled by
+    status: Option<Status>,   // result of the last action
+    diff: Option<DiffView>,
+    last_click: Option<(Instant, usize)>, // time and row of the last click
+    dragging_scrollbar: bool,
 }
 
 impl App {
-    fn new() -> Self {
-        let data_vec = get_trash_info();
-        Self {
+    fn new(on_conflict: ConflictPolicy, config: Config) -> Self {
+        let mut app = Self {
+            dates: DateDisplay::new(config.date_format),
+            keymap: config.keymap,
+            help: false,
             state: TableState::default().with_selected(0),
-            longest_item_lens: constraint_len_calculator(&data_vec),
-            scroll_state: ScrollbarState::new((data_vec.len() - 1) * ITEM_HEIGHT),
+            longest_item_lens: constraint_len_calculator(&[]),
+            scroll_state: ScrollbarState::new(0),
+            grouped: false,
+            groups: Vec::new(),
+            collapsed: HashSet::new(),
+            row_offset: 0,
             colors: TableColors::new(&PALETTES[0]),
             color_index: 0,
-            items: data_vec,
+            on_conflict,
+            pending_conflicts: VecDeque::new(),
+            browser: None,
+            path_input: None,
+            pending_action: None,
+            marked: HashSet::new(),
+            removed: HashSet::new(),
+            visual_anchor: None,
+            show_preview: false,
+            details: None,
+            preview_depth: 1,
+            preview: None,
+            watcher: TrashWatcher::new(),
+            loading: Some(load_trash_in_background()),
+            reloaded: None,
+            spinner: 0,
+            table_area: Rect::default(),
+            columns: Vec::new(),
+            h_scroll: 0,
+            status: None,
+            diff: None,
+            last_click: None,
+            dragging_scrollbar: false,
+            items: Vec::new(),
+            visible: Vec::new(),
+            search: String::new(),
+            searching: false,
+            sort_column: SortColumn::Date,
+            sort_descending: true,
+        };
+        app.sort_items();
+        app.refresh_rows(None);
+        app
+    }
+
+    /// Rereads the trash after another program changed it. The current
+    /// entries stay on screen until the new list is complete.
+    fn reload(&mut self) {
+        self.loading = Some(load_trash_in_background());
+        self.reloaded = Some(Vec::new());
+    }
+
+    /// Takes whatever the background loader has read so far.
+    fn poll_loader(&mut self) {
+        let Some(batches) = &self.loading else {
+            return;
+        };
+        let mut received = Vec::new();
+        let done = loop {
+            match batches.try_recv() {
+                Ok(batch) => received.extend(batch),
+                Err(TryRecvError::Empty) => break false,
+                Err(TryRecvError::Disconnected) => break true,
+            }
+        };
+
+        let selected = self.selected_name();
+        match &mut self.reloaded {
+            Some(pending) => pending.extend(received),
+            None if received.is_empty() => {}
+            None => {
+                self.longest_item_lens =
+                    widest(self.longest_item_lens, constraint_len_calculator(&received));
+                self.items.extend(received);
+                self.sort_items();
+                self.refresh_rows(selected.clone());
+            }
+        }
+
+        if done {
+            self.loading = None;
+            if let Some(items) = self.reloaded.take() {
+                self.replace_items(items, selected);
+            }
+        }
+    }
+
+    /// Swaps in a freshly loaded list, keeping the selection and marks on
+    /// the same entries.
+    fn replace_items(&mut self, items: Vec<TrashEntry>, selected: Option<String>) {
+        self.items = items;
+        self.longest_item_lens = constraint_len_calculator(&self.items);
+
+        let names: HashSet<&str> = self.items.iter().map(TrashEntry::file).collect();
+        self.marked.retain(|name| names.contains(name.as_str()));
+        if let Some(browser) = &mut self.browser {
+            if names.contains(browser.name.as_str()) {
+                browser.load();
+            } else {
+                self.browser = None;
+            }
+        }
+        self.preview = None;
+
+        self.sort_items();
+        self.refresh_rows(selected);
+    }
+
+    /// Sorts by `column`, or flips the direction if already sorted by it.
+    pub fn sort_by(&mut self, column: SortColumn) {
+        if self.sort_column == column {
+            self.sort_descending = !self.sort_descending;
+        } else {
+            self.sort_column = column;
+            // Newest and biggest first, alphabetical otherwise.
+            self.sort_descending = matches!(column, SortColumn::Date | SortColumn::Size);
+        }
+        let selected = self.selected_name();
+        self.sort_items();
+        self.refresh_rows(selected);
+    }
+
+    /// Sorts by the column the cursor is on.
+    pub fn sort_by_selected_column(&mut self) {
+        let column = self.state.selected_column().unwrap_or(0);
+        self.sort_by(SortColumn::ALL[column.min(SortColumn::ALL.len() - 1)]);
+    }
+
+    /// Moves the sort to the next column.
+    pub fn next_sort_column(&mut self) {
+        let i = SortColumn::ALL
+            .iter()
+            .position(|&column| column == self.sort_column)
+            .unwrap_or(0);
+        self.sort_by(SortColumn::ALL[(i + 1) % SortColumn::ALL.len()]);
+    }
+
+    pub fn reverse_sort(&mut self) {
+        self.sort_by(self.sort_column);
+    }
+
+    fn sort_items(&mut self) {
+        let column = self.sort_column;
+        // Ties fall back to the name so that the order is stable across reloads.
+        self.items
+            .sort_by(|a, b| column.compare(a, b).then_with(|| a.file.cmp(&b.file)));
+        if self.sort_descending {
+            self.items.reverse();
+        }
+    }
+
+    /// Index into `items` of the selected row.
+    fn selected_item(&self) -> Option<usize> {
+        self.state
+            .selected()
+            .and_then(|row| self.visible.get(row))
+            .filter(|row| row.group.is_none())
+            .map(|row| row.item)
+    }
+
+    /// The group whose header is selected, in grouped mode.
+    fn selected_group(&self) -> Option<&Group> {
+        let row = self.visible.get(self.state.selected()?)?;
+        self.groups.get(row.group?)
+    }
+
+    /// Arranges `rows` under a header per original parent directory. Groups
+    /// follow the sort column, and entries keep their order within a group.
+    fn group_rows(&mut self, rows: Vec<VisibleRow>) -> Vec<VisibleRow> {
+        let mut groups: Vec<Group> = Vec::new();
+        let mut members: Vec<Vec<VisibleRow>> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+
+        for row in rows {
+            let entry = &self.items[row.item];
+            let dir = Path::new(&entry.path)
+                .parent()
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_default();
+            let g = *index.entry(dir.clone()).or_insert_with(|| {
+                groups.push(Group {
+                    dir,
+                    names: Vec::new(),
+                    size: 0,
+                    latest: None,
+                });
+                members.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[g].names.push(entry.file.clone());
+            groups[g].size += entry.size;
+            groups[g].latest = groups[g].latest.max(entry.deleted_at);
+            members[g].push(row);
+        }
+
+        let mut order: Vec<usize> = (0..groups.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (a, b) = (&groups[a], &groups[b]);
+            let ordering = match self.sort_column {
+                SortColumn::Name | SortColumn::Path => Ordering::Equal,
+                SortColumn::Date => a.latest.cmp(&b.latest),
+                SortColumn::Size => a.size.cmp(&b.size),
+            };
+            ordering.then_with(|| a.dir.cmp(&b.dir))
+        });
+        if self.sort_descending {
+            order.reverse();
+        }
+
+        let mut visible =
+            Vec::with_capacity(members.iter().map(Vec::len).sum::<usize>() + order.len());
+        for g in order {
+            let rows = std::mem::take(&mut members[g]);
+            visible.push(VisibleRow {
+                item: rows[0].item,
+                score: rows.iter().map(|row| row.score).max().unwrap_or(0),
+                file_hits: Vec::new(),
+                path_hits: Vec::new(),
+                group: Some(g),
+            });
+            if !self.collapsed.contains(&groups[g].dir) {
+                visible.extend(rows);
+            }
+        }
+        self.groups = groups;
+        visible
+    }
+
+    /// Switches between the flat list and the tree grouped by origin.
+    pub fn toggle_grouped(&mut self) {
+        let selected = self.selected_name();
+        self.grouped = !self.grouped;
+        self.refresh_rows(selected);
+    }
+
+    /// Collapses or expands the selected group. Returns false if no group
+    /// header is selected.
+    fn toggle_group(&mut self) -> bool {
+        let Some(dir) = self.selected_group().map(|group| group.dir.clone()) else {
+            return false;
+        };
+        if !self.collapsed.remove(&dir) {
+            self.collapsed.insert(dir);
+        }
+        // The header stays where it is, only the rows below it change.
+        self.refresh_rows(None);
+        true
+    }
+
+    fn selected_name(&self) -> Option<String> {
+        self.selected_item().map(|i| self.items[i].file.clone())
+    }
+
+    /// Rebuilds the visible rows after the items or the search changed. The
+    /// selection stays on the entry called `selected` if it is still visible,
+    /// otherwise it moves to the best match, or stays at the same height.
+    fn refresh_rows(&mut self, selected: Option<String>) {
+        let previous_row = self.state.selected().unwrap_or(0);
+        self.visual_anchor = None;
+
+        self.visible = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(item, entry)| {
+                if self.search.is_empty() {
+                    return Some(VisibleRow {
+                        item,
+                        score: 0,
+                        file_hits: Vec::new(),
+                        path_hits: Vec::new(),
+                        group: None,
+                    });
+                }
+                let file = fuzzy::fuzzy_match(&self.search, &entry.file);
+                let path = fuzzy::fuzzy_match(&self.search, &entry.path);
+                let score = file.iter().chain(&path).map(|m| m.score).max()?;
+                Some(VisibleRow {
+                    item,
+                    score,
+                    file_hits: file.map(|m| m.positions).unwrap_or_default(),
+                    path_hits: path.map(|m| m.positions).unwrap_or_default(),
+                    group: None,
+                })
+            })
+            .collect();
+        if self.grouped {
+            let rows = std::mem::take(&mut self.visible);
+            self.visible = self.group_rows(rows);
+        }
+
+        let row = selected
+            .and_then(|name| {
+                self.visible
+                    .iter()
+                    .position(|row| row.group.is_none() && self.items[row.item].file == name)
+            })
+            .or_else(|| {
+                if self.search.is_empty() {
+                    self.visible
+                        .len()
+                        .checked_sub(1)
+                        .map(|last| previous_row.min(last))
+                } else {
+                    (0..self.visible.len()).max_by_key(|&row| self.visible[row].score)
+                }
+            });
+
+        self.state.select(row);
+        self.scroll_state = self
+            .scroll_state
+            .content_length(self.visible.len().saturating_sub(1) * ITEM_HEIGHT)
+            .position(row.unwrap_or(0) * ITEM_HEIGHT);
+    }
+
+    pub fn start_search(&mut self) {
+        self.searching = true;
+    }
+
+    fn update_search(&mut self, search: String) {
+        let selected = self.selected_name();
+        self.search = search;
+        self.refresh_rows(selected);
+    }
+
+    pub fn clear_search(&mut self) {
+        self.searching = false;
+        self.update_search(String::new());
+    }
+
+    /// Selects `row`, or the last row if it is past the end.
+    fn select_row(&mut self, row: usize) {
+        if self.visible.is_empty() {
+            return;
         }
+        let row = row.min(self.visible.len() - 1);
+        if self.state.selected() != Some(row) {
+            self.h_scroll = 0;
+        }
+        self.state.select(Some(row));
+        self.scroll_state = self.scroll_state.position(row * ITEM_HEIGHT);
     }
 
     pub fn next_row(&mut self) {
+        if self.visible.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
-                if i >= self.items.len() - 1 {
+                if i >= self.visible.len() - 1 {
                     0
                 } else {
                     i + 1
@@ -130,31 +904,51 @@ impl App {
             }
             None => 0,
         };
-        self.state.select(Some(i));
-        self.scroll_state = self.scroll_state.position(i * ITEM_HEIGHT);
+        self.select_row(i);
     }
 
     pub fn previous_row(&mut self) {
+        if self.visible.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.items.len() - 1
+                    self.visible.len() - 1
                 } else {
                     i - 1
                 }
             }
             None => 0,
         };
-        self.state.select(Some(i));
-        self.scroll_state = self.scroll_state.position(i * ITEM_HEIGHT);
+        self.select_row(i);
     }
 
     pub fn next_column(&mut self) {
-        self.state.select_next_column();
+        self.step_column(true);
     }
 
     pub fn previous_column(&mut self) {
-        self.state.select_previous_column();
+        self.step_column(false);
+    }
+
+    /// Moves the column cursor, skipping the columns hidden for lack of
+    /// room.
+    fn step_column(&mut self, forward: bool) {
+        let shown: Vec<usize> = (0..SortColumn::ALL.len())
+            .filter(|&c| self.columns.get(c).is_none_or(|&(_, width)| width > 0))
+            .collect();
+        let current = self
+            .state
+            .selected_column()
+            .and_then(|c| shown.iter().position(|&s| s == c));
+        let next = match current {
+            None => 0,
+            Some(i) if forward => (i + 1).min(shown.len() - 1),
+            Some(i) => i.saturating_sub(1),
+        };
+        self.state.select_column(shown.get(next).copied());
+        self.h_scroll = 0;
     }
 
     pub fn next_color(&mut self) {
@@ -170,173 +964,1565 @@ impl App {
         self.colors = TableColors::new(&PALETTES[self.color_index]);
     }
 
-    // TODO
-    pub fn delete_selection(&mut self) {
-        self.items.pop();
+    /// Toggles the mark on the selected row and moves to the next one.
+    pub fn toggle_mark(&mut self) {
+        if let Some(group) = self.selected_group() {
+            // Mark the whole group, or unmark it if it is already marked.
+            let names = group.names.clone();
+            if names.iter().all(|name| self.marked.contains(name)) {
+                names.iter().for_each(|name| {
+                    self.marked.remove(name);
+                });
+            } else {
+                self.marked.extend(names);
+            }
+            self.next_row();
+        } else if let Some(name) = self.selected_name() {
+            if !self.marked.remove(&name) {
+                self.marked.insert(name);
+            }
+            self.next_row();
+        }
     }
 
-    // TOOD
-    pub fn restore_selection(&mut self) {
-        self.items.pop();
+    /// Marks every unmarked visible row and unmarks every marked one.
+    pub fn invert_marks(&mut self) {
+        for row in self.visible.iter().filter(|row| row.group.is_none()) {
+            let name = &self.items[row.item].file;
+            if !self.marked.remove(name) {
+                self.marked.insert(name.clone());
+            }
+        }
     }
 
-    fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
-        loop {
-            terminal.draw(|frame| self.draw(frame))?;
-
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    let shift_pressed = key
-                        .modifiers
-                        .contains(ratatui::crossterm::event::KeyModifiers::SHIFT);
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
-                        KeyCode::Char('j') | KeyCode::Down => self.next_row(),
-                        KeyCode::Char('k') | KeyCode::Up => self.previous_row(),
-                        KeyCode::Char('l') | KeyCode::Right if shift_pressed => self.next_color(),
-                        KeyCode::Char('h') | KeyCode::Left if shift_pressed => {
-                            self.previous_color();
-                        }
-                        KeyCode::Char('d') => self.delete_selection(),
-                        KeyCode::Char('r') => self.restore_selection(),
-                        KeyCode::Char('l') | KeyCode::Right => self.next_column(),
-                        KeyCode::Char('h') | KeyCode::Left => self.previous_column(),
-                        _ => {}
+    /// Starts a visual range at the selected row, or marks the range if one
+    /// was already started.
+    pub fn toggle_visual(&mut self) {
+        let Some(cursor) = self.state.selected() else {
+            return;
+        };
+        match self.visual_anchor.take() {
+            None => self.visual_anchor = Some(cursor),
+            Some(anchor) => {
+                for row in anchor.min(cursor)..=anchor.max(cursor) {
+                    if let Some(row) = self.visible.get(row).filter(|row| row.group.is_none()) {
+                        self.marked.insert(self.items[row.item].file.clone());
                     }
                 }
             }
         }
     }
 
-    fn draw(&mut self, frame: &mut Frame) {
-        let vertical = &Layout::vertical([Constraint::Min(5), Constraint::Length(4)]);
-        let rects = vertical.split(frame.area());
-
-        self.set_colors();
-
-        self.render_table(frame, rects[0]);
-        self.render_scrollbar(frame, rects[0]);
-        self.render_footer(frame, rects[1]);
+    /// True if the visible row `row` is marked or inside the visual range.
+    fn is_marked(&self, row: usize) -> bool {
+        let in_range = match (self.visual_anchor, self.state.selected()) {
+            (Some(anchor), Some(cursor)) => {
+                (anchor.min(cursor)..=anchor.max(cursor)).contains(&row)
+            }
+            _ => false,
+        };
+        let row = &self.visible[row];
+        match row.group {
+            Some(g) => self.groups[g]
+                .names
+                .iter()
+                .all(|name| self.marked.contains(name)),
+            None => in_range || self.marked.contains(&self.items[row.item].file),
+        }
     }
 
-    fn render_table(&mut self, frame: &mut Frame, area: Rect) {
-        let header_style = Style::default()
-            .fg(self.colors.header_fg)
-            .bg(self.colors.header_bg);
-        let selected_row_style = Style::default()
-            .add_modifier(Modifier::REVERSED)
-            .fg(self.colors.selected_row_style_fg);
-        let selected_col_style = Style::default().fg(self.colors.selected_column_style_fg);
-        let selected_cell_style = Style::default()
-            .add_modifier(Modifier::REVERSED)
-            .fg(self.colors.selected_cell_style_fg);
+    /// Names of the entries an action applies to: the marked ones in display
+    /// order, or the selected one if nothing is marked.
+    fn action_targets(&self) -> Vec<String> {
+        if self.marked.is_empty() {
+            if let Some(group) = self.selected_group() {
+                return group.names.clone();
+            }
+            return self.selected_name().into_iter().collect();
+        }
+        self.items
+            .iter()
+            .filter(|entry| self.marked.contains(&entry.file))
+            .map(|entry| entry.file.clone())
+            .collect()
+    }
 
-        let header = ["File Name", "Original Path", "Date"]
-            .into_iter()
-            .map(Cell::from)
-            .collect::<Row>()
-            .style(header_style)
-            .height(1);
-        let rows = self.items.iter().enumerate().map(|(i, data)| {
-            let color = match i % 2 {
-                0 => self.colors.normal_row_color,
-                _ => self.colors.alt_row_color,
-            };
-            let item = data.ref_array();
-            item.into_iter()
-                .map(|content| Cell::from(Text::from(format!("\n{content}\n"))))
-                .collect::<Row>()
-                .style(Style::new().fg(self.colors.row_fg).bg(color))
-                .height(2)
-        });
-        let bar = " █ ";
-        let t = Table::new(
-            rows,
-            [
-                // + 1 is for padding.
-                Constraint::Length(self.longest_item_lens.0 + 1),
-                Constraint::Min(self.longest_item_lens.1 + 1),
-                Constraint::Min(self.longest_item_lens.2),
-            ],
-        )
-        .header(header)
-        .row_highlight_style(selected_row_style)
-        .column_highlight_style(selected_col_style)
-        .cell_highlight_style(selected_cell_style)
-        .highlight_symbol(Text::from(vec![
-            "".into(),
-            bar.into(),
-            bar.into(),
-            "".into(),
-        ]))
-        .bg(self.colors.buffer_bg)
-        .highlight_spacing(HighlightSpacing::Always);
-        frame.render_stateful_widget(t, area, &mut self.state);
+    fn total_size<'a>(&self, names: impl IntoIterator<Item = &'a String>) -> u64 {
+        let names: HashSet<&String> = names.into_iter().collect();
+        self.items
+            .iter()
+            .filter(|entry| names.contains(&entry.file))
+            .map(|entry| entry.size)
+            .sum()
     }
 
-    fn render_scrollbar(&mut self, frame: &mut Frame, area: Rect) {
-        frame.render_stateful_widget(
-            Scrollbar::default()
-                .orientation(ScrollbarOrientation::VerticalRight)
-                .begin_symbol(None)
-                .end_symbol(None),
-            area.inner(Margin {
-                vertical: 1,
-                horizontal: 1,
-            }),
-            &mut self.scroll_state,
-        );
+    /// Asks to permanently delete the marked entries, or the selected one.
+    pub fn delete_selection(&mut self) {
+        let names = self.action_targets();
+        if !names.is_empty() {
+            self.pending_action = Some(BulkAction::Delete(names));
+        }
     }
 
-    fn render_footer(&self, frame: &mut Frame, area: Rect) {
-        let info_footer = Paragraph::new(Text::from_iter(INFO_TEXT))
-            .style(
-                Style::new()
-                    .fg(self.colors.row_fg)
-                    .bg(self.colors.buffer_bg),
-            )
-            .centered()
-            .block(
-                Block::bordered()
-                    .border_type(BorderType::Double)
-                    .border_style(Style::new().fg(self.colors.footer_border_color)),
-            );
-        frame.render_widget(info_footer, area);
+    /// Restores the selected entry, or asks to restore all marked entries or
+    /// the selected group.
+    pub fn restore_selection(&mut self) {
+        let names = self.action_targets();
+        if self.marked.is_empty() && self.selected_group().is_none() {
+            self.restore_names(names);
+        } else if !names.is_empty() {
+            self.pending_action = Some(BulkAction::Restore(names));
+        }
     }
-}
 
-fn get_trash_info() -> Vec<TrashEntry> {
-    let home_dir = dirs_next::home_dir().expect("Failed to get home directory");
-    let trash_dir = home_dir.join(".local/share/Trash/files");
+    /// Opens the destination prompt for restoring the marked entries, or the
+    /// selected one, elsewhere.
+    pub fn restore_selection_to(&mut self) {
+        let names = self.action_targets();
+        if !names.is_empty() {
+            self.path_input = Some((names, String::new()));
+        }
+    }
 
-    if !trash_dir.exists() {
-        println!("Trash directory does not exist at {:?}", trash_dir);
-        return Vec::new();
+    /// Asks to permanently delete everything in the trash.
+    pub fn confirm_empty(&mut self) {
+        if !self.items.is_empty() {
+            self.pending_action = Some(BulkAction::Empty);
+        }
     }
 
-    let mut entries: Vec<TrashEntry> = Vec::new();
+    fn run_pending_action(&mut self) {
+        match self.pending_action.take() {
+            Some(BulkAction::Restore(names)) => self.restore_names(names),
+            Some(BulkAction::Delete(names)) => self.delete_names(names),
+            Some(BulkAction::Empty) => self.empty_trash(),
+            None => {}
+        }
+    }
 
-    match fs::read_dir(&trash_dir) {
-        Ok(dir_entries) => {
-            for entry in dir_entries {
-                if let Ok(entry) = entry {
-                    let file_name = entry.file_name().to_string_lossy().to_string();
-                    let original_path = get_info_from_trashinfo(&file_name, "Path=")
-                        .unwrap_or_else(|| "Unknown".to_string());
-                    let date_info = get_info_from_trashinfo(&file_name, "DeletionDate=")
-                        .unwrap_or_else(|| "Unknown".to_string());
+    fn restore_names(&mut self, names: Vec<String>) {
+        let results = names
+            .into_iter()
+            .filter_map(|name| {
+                let result = match trash::read_item(&name) {
+                    Ok(item) => self.restore_entry_to(PathBuf::from(&name), item.original_path)?,
+                    Err(e) => Err(e),
+                };
+                Some((name, result))
+            })
+            .collect();
+        self.report("restore", "restored", results);
+    }
 
-                    entries.push(TrashEntry {
-                        file: file_name,
-                        path: original_path,
-                        date: date_info,
-                    });
+    fn delete_names(&mut self, names: Vec<String>) {
+        let results = names
+            .into_iter()
+            .map(|name| {
+                let result = trash::read_item(&name).and_then(|item| empty::purge_item(&item));
+                if result.is_ok() {
+                    self.remove_entry(&name);
                 }
+                (name, result.map(|()| true))
+            })
+            .collect();
+        self.report("delete", "deleted", results);
+    }
+
+    fn empty_trash(&mut self) {
+        let count = self.items.len();
+        match empty::delete_all() {
+            Ok(()) => self.set_status(format!("deleted {} item(s), the trash is empty", count)),
+            Err(e) => self.set_error(format!("cannot empty the trash: {}", e)),
+        }
+        self.marked.clear();
+        self.reload();
+    }
+
+    /// Restores `source`, a trash entry or a path inside one relative to
+    /// files/, to `dest`. Returns None if the user is asked about a conflict
+    /// first, otherwise whether it was restored or skipped.
+    fn restore_entry_to(&mut self, source: PathBuf, dest: PathBuf) -> Option<io::Result<bool>> {
+        // There is no stdin to prompt on here, so ask through a popup instead.
+        if self.on_conflict == ConflictPolicy::Ask && trash::path_exists(&dest) {
+            self.pending_conflicts.push_back((source, dest));
+            return None;
+        }
+        Some(self.restore_entry(&source, &dest, self.on_conflict))
+    }
+
+    fn restore_entry(
+        &mut self,
+        source: &Path,
+        dest: &Path,
+        policy: ConflictPolicy,
+    ) -> io::Result<bool> {
+        let mut components = source.components();
+        let Some(name) = components.next() else {
+            return Ok(false);
+        };
+        let name = name.as_os_str().to_string_lossy().into_owned();
+        let relative = components.as_path();
+        let item = trash::read_item(&name)?;
+
+        if relative.as_os_str().is_empty() {
+            let restored = restore::restore_item_to(&item, dest, policy)?.is_some();
+            if restored {
+                self.remove_entry(&name);
+            }
+            Ok(restored)
+        } else {
+            let restored = restore::restore_inner(&item, relative, dest, policy)?.is_some();
+            if restored {
+                self.reload_entry(&name);
+            }
+            Ok(restored)
+        }
+    }
+
+    /// Shows how restoring or deleting entries went. Each result says
+    /// whether the entry was done or skipped.
+    fn report(&mut self, verb: &str, done: &str, results: Vec<(String, io::Result<bool>)>) {
+        if results.is_empty() {
+            return;
+        }
+        let count = results
+            .iter()
+            .filter(|(_, r)| matches!(r, Ok(true)))
+            .count();
+        let skipped = results
+            .iter()
+            .filter(|(_, r)| matches!(r, Ok(false)))
+            .count();
+        let errors: Vec<String> = results
+            .into_iter()
+            .filter_map(|(name, result)| {
+                result
+                    .err()
+                    .map(|e| format!("cannot {} '{}': {}", verb, name, e))
+            })
+            .collect();
+
+        let mut text = format!("{} {} item(s)", done, count);
+        if skipped > 0 {
+            text.push_str(&format!(", skipped {}", skipped));
+        }
+        match errors.as_slice() {
+            [] => self.set_status(text),
+            [error] if count == 0 && skipped == 0 => self.set_error(error.clone()),
+            [first, ..] => self.set_error(format!("{}, {} failed: {}", text, errors.len(), first)),
+        }
+    }
+
+    fn set_status(&mut self, text: String) {
+        self.status = Some(Status {
+            text,
+            error: false,
+            shown_at: Instant::now(),
+        });
+    }
+
+    fn set_error(&mut self, text: String) {
+        self.status = Some(Status {
+            text,
+            error: true,
+            shown_at: Instant::now(),
+        });
+    }
+
+    /// Clears the status message once it has been shown long enough.
+    fn expire_status(&mut self) {
+        if let Some(status) = &self.status {
+            let timeout = if status.error {
+                ERROR_TIMEOUT
+            } else {
+                STATUS_TIMEOUT
+            };
+            if status.shown_at.elapsed() >= timeout {
+                self.status = None;
             }
         }
-        Err(_) => {}
     }
-    return entries;
+
+    /// Rereads an entry whose contents changed, i.e. after restoring a file
+    /// from inside it.
+    fn reload_entry(&mut self, name: &str) {
+        if let Some(entry) = self.items.iter_mut().find(|entry| entry.file == name) {
+            *entry = TrashEntry::load(name.to_string());
+        }
+        if let Some(browser) = &mut self.browser {
+            browser.load();
+        }
+    }
+
+    /// Descends into the selected entry if it is a trashed directory.
+    /// Returns false if it is not one.
+    fn open_browser(&mut self) -> bool {
+        self.browser = self.selected_name().as_deref().and_then(Browser::open);
+        self.browser.is_some()
+    }
+
+    fn restore_browser_selection(&mut self) {
+        let Some(browser) = &self.browser else {
+            return;
+        };
+        if let (Some(source), Some(dest)) = (browser.selected_source(), browser.selected_dest())
+            && let Some(result) = self.restore_entry_to(source.clone(), dest)
+        {
+            self.report(
+                "restore",
+                "restored",
+                vec![(source.display().to_string(), result)],
+            );
+        }
+    }
+
+    /// Opens the diff of the selected entry against its original path.
+    fn show_diff(&mut self) {
+        let Some(name) = self.preview_target() else {
+            return;
+        };
+        match diff::diff_source(Path::new(&name)) {
+            Ok(lines) => {
+                self.diff = Some(DiffView {
+                    name,
+                    lines,
+                    scroll: 0,
+                    page: 1,
+                });
+            }
+            Err(e) => self.set_error(format!("cannot diff '{}': {}", name, e)),
+        }
+    }
+
+    /// Opens the details popup for the selected entry.
+    fn show_details(&mut self) {
+        if let Some(name) = self.selected_name() {
+            let lines = item_details(&name, self.on_conflict);
+            self.details = Some((name, lines));
+        }
+    }
+
+    /// Copies the selected cell, or in a directory the path the selected
+    /// file would be restored to. The original path is copied in full, not
+    /// as shown with ~.
+    fn copy_selection(&mut self) {
+        let text = if let Some(browser) = &self.browser {
+            browser
+                .selected_dest()
+                .map(|path| path.display().to_string())
+        } else {
+            let column = self.state.selected_column().unwrap_or(0);
+            self.selected_item().map(|i| {
+                let entry = &self.items[i];
+                match SortColumn::ALL[column] {
+                    SortColumn::Path => trash::read_item(&entry.file)
+                        .map(|item| item.original_path.display().to_string())
+                        .unwrap_or_else(|_| entry.path.clone()),
+                    _ => self
+                        .cell_text(entry, column, Local::now().naive_local())
+                        .into_owned(),
+                }
+            })
+        };
+        if let Some(text) = text {
+            self.copy_text(text);
+        }
+    }
+
+    /// Copies where the selected file is kept inside the trash.
+    fn copy_location(&mut self) {
+        let Some(name) = self.preview_target() else {
+            return;
+        };
+        match trash::files_dir() {
+            Ok(dir) => self.copy_text(dir.join(name).display().to_string()),
+            Err(e) => self.set_error(format!("cannot copy '{}': {}", name, e)),
+        }
+    }
+
+    fn copy_text(&mut self, text: String) {
+        match clipboard::copy(&text) {
+            Ok(()) => self.set_status(format!("copied '{}'", text)),
+            Err(e) => self.set_error(format!("cannot copy to the clipboard: {}", e)),
+        }
+    }
+
+    /// Path of the row the preview pane should show, relative to files/.
+    fn preview_target(&self) -> Option<String> {
+        match &self.browser {
+            Some(browser) => browser
+                .selected_source()
+                .map(|path| path.to_string_lossy().into_owned()),
+            None => self.selected_name(),
+        }
+    }
+
+    /// Drops an entry that left the trash. Bulk actions remove many, so the
+    /// rows are only rebuilt once, by [`App::drop_removed`].
+    fn remove_entry(&mut self, name: &str) {
+        self.removed.insert(name.to_string());
+    }
+
+    fn drop_removed(&mut self) {
+        if self.removed.is_empty() {
+            return;
+        }
+        let removed = std::mem::take(&mut self.removed);
+        let selected = self.selected_name();
+        self.items.retain(|entry| !removed.contains(&entry.file));
+        self.marked.retain(|name| !removed.contains(name));
+        self.refresh_rows(selected);
+    }
+
+    fn resolve_pending_conflict(&mut self, policy: Option<ConflictPolicy>) {
+        if let (Some((source, dest)), Some(policy)) = (self.pending_conflicts.pop_front(), policy) {
+            let result = self.restore_entry(&source, &dest, policy);
+            self.report(
+                "restore",
+                "restored",
+                vec![(source.display().to_string(), result)],
+            );
+        }
+    }
+
+    fn submit_path_input(&mut self) {
+        let Some((names, input)) = self.path_input.take() else {
+            return;
+        };
+        if input.trim().is_empty() {
+            return;
+        }
+        let dir = trash::expand_tilde(input.trim());
+        let results = names
+            .into_iter()
+            .filter_map(|name| {
+                let result = match trash::read_item(&name) {
+                    Ok(item) => {
+                        self.restore_entry_to(PathBuf::from(&name), dir.join(item.original_name()))?
+                    }
+                    Err(e) => Err(e),
+                };
+                Some((name, result))
+            })
+            .collect();
+        self.report("restore", "restored", results);
+    }
+
+    fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
+        loop {
+            self.poll_loader();
+            self.drop_removed();
+            self.expire_status();
+            terminal.draw(|frame| self.draw(frame))?;
+
+            // Wake up now and then to pick up changes made by other programs,
+            // and more often while the list is still loading.
+            let timeout = if self.loading.is_some() {
+                self.spinner = self.spinner.wrapping_add(1);
+                LOADING_INTERVAL
+            } else {
+                REFRESH_INTERVAL
+            };
+            // A failed read is reported rather than ending the session, and
+            // waits out the timeout so a persistent failure does not spin.
+            let event = match event::poll(timeout).and_then(|ready| {
+                if ready {
+                    event::read().map(Some)
+                } else {
+                    Ok(None)
+                }
+            }) {
+                Ok(event) => event,
+                Err(e) => {
+                    self.set_error(format!("cannot read input: {}", e));
+                    thread::sleep(timeout);
+                    continue;
+                }
+            };
+            let key = match event {
+                None => {
+                    if self.watcher.as_ref().is_some_and(TrashWatcher::changed) {
+                        self.reload();
+                    }
+                    continue;
+                }
+                Some(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+                Some(Event::Mouse(mouse)) => {
+                    self.handle_mouse(mouse);
+                    continue;
+                }
+                Some(_) => continue,
+            };
+
+            if !self.pending_conflicts.is_empty() {
+                match key.code {
+                    KeyCode::Char('r') => {
+                        self.resolve_pending_conflict(Some(ConflictPolicy::Rename));
+                    }
+                    KeyCode::Char('o') => {
+                        self.resolve_pending_conflict(Some(ConflictPolicy::Overwrite));
+                    }
+                    KeyCode::Char('s') | KeyCode::Char('q') | KeyCode::Esc => {
+                        self.resolve_pending_conflict(None);
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            if self.details.is_some() {
+                // Any key closes the popup
+                self.details = None;
+                continue;
+            }
+
+            if let Some(diff) = self.diff.as_mut() {
+                match (self.keymap.action(&key), key.code) {
+                    (Some(Action::Down), _) => diff.scroll += 1,
+                    (Some(Action::Up), _) => diff.scroll = diff.scroll.saturating_sub(1),
+                    (_, KeyCode::PageDown | KeyCode::Char(' ')) => diff.scroll += diff.page,
+                    (_, KeyCode::PageUp) => diff.scroll = diff.scroll.saturating_sub(diff.page),
+                    (_, KeyCode::Home) => diff.scroll = 0,
+                    (_, KeyCode::End) => diff.scroll = usize::MAX,
+                    (Some(Action::Quit | Action::Diff), _) => self.diff = None,
+                    _ => {}
+                }
+                continue;
+            }
+
+            if self.pending_action.is_some() {
+                match key.code {
+                    KeyCode::Char('y') => self.run_pending_action(),
+                    KeyCode::Char('n') | KeyCode::Char('q') | KeyCode::Esc => {
+                        self.pending_action = None;
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            if self.searching {
+                match key.code {
+                    KeyCode::Enter => self.searching = false,
+                    KeyCode::Esc => self.clear_search(),
+                    KeyCode::Backspace => {
+                        let mut search = self.search.clone();
+                        search.pop();
+                        self.update_search(search);
+                    }
+                    KeyCode::Char(c) => self.update_search(format!("{}{}", self.search, c)),
+                    KeyCode::Down => self.next_row(),
+                    KeyCode::Up => self.previous_row(),
+                    _ => {}
+                }
+                continue;
+            }
+
+            if let Some((_, input)) = self.path_input.as_mut() {
+                match key.code {
+                    KeyCode::Enter => self.submit_path_input(),
+                    KeyCode::Esc => self.path_input = None,
+                    KeyCode::Backspace => {
+                        input.pop();
+                    }
+                    KeyCode::Char(c) => input.push(c),
+                    _ => {}
+                }
+                continue;
+            }
+
+            if self.help {
+                // Any key closes the help
+                self.help = false;
+                continue;
+            }
+
+            let action = self.keymap.action(&key);
+            if let Some(browser) = self.browser.as_mut() {
+                match action {
+                    _ if key.code == KeyCode::Backspace && !browser.up() => {
+                        self.browser = None;
+                    }
+                    Some(Action::Quit) => self.browser = None,
+                    Some(Action::Left) if !browser.up() => self.browser = None,
+                    Some(Action::Right | Action::Open) => browser.enter(),
+                    Some(Action::Down) => browser.next_row(),
+                    Some(Action::Up) => browser.previous_row(),
+                    Some(Action::Restore) => self.restore_browser_selection(),
+                    Some(Action::Preview) => self.show_preview = !self.show_preview,
+                    Some(Action::Diff) => self.show_diff(),
+                    Some(Action::Pager) => self.open_external(&mut terminal, open::pager),
+                    Some(Action::Editor) => self.open_external(&mut terminal, open::editor),
+                    Some(Action::Copy) => self.copy_selection(),
+                    Some(Action::CopyLocation) => self.copy_location(),
+                    Some(Action::Help) => self.help = true,
+                    _ => {}
+                }
+                continue;
+            }
+
+            let Some(action) = action else {
+                continue;
+            };
+            match action {
+                Action::Quit if self.visual_anchor.is_some() => self.visual_anchor = None,
+                Action::Quit if !self.search.is_empty() => self.clear_search(),
+                Action::Quit => return Ok(()),
+                Action::Down | Action::NextMatch => self.next_row(),
+                Action::Up | Action::PreviousMatch => self.previous_row(),
+                Action::Left => self.previous_column(),
+                Action::Right => self.next_column(),
+                Action::ScrollLeft => self.h_scroll = self.h_scroll.saturating_sub(SCROLL_STEP),
+                Action::ScrollRight => self.h_scroll += SCROLL_STEP,
+                Action::Search => self.start_search(),
+                Action::Open => {
+                    if !self.toggle_group() && !self.open_browser() {
+                        self.sort_by_selected_column();
+                    }
+                }
+                Action::NextSort => self.next_sort_column(),
+                Action::ReverseSort => self.reverse_sort(),
+                Action::Mark => self.toggle_mark(),
+                Action::Visual => self.toggle_visual(),
+                Action::InvertMarks => self.invert_marks(),
+                Action::Restore => self.restore_selection(),
+                Action::RestoreTo => self.restore_selection_to(),
+                Action::Delete => self.delete_selection(),
+                Action::Empty => self.confirm_empty(),
+                Action::Preview => self.show_preview = !self.show_preview,
+                Action::Expand if self.show_preview => self.preview_depth += 1,
+                Action::Collapse if self.show_preview && self.preview_depth > 1 => {
+                    self.preview_depth -= 1;
+                }
+                Action::Expand | Action::Collapse => {}
+                Action::Info => self.show_details(),
+                Action::Diff => self.show_diff(),
+                Action::Pager => self.open_external(&mut terminal, open::pager),
+                Action::Editor => self.open_external(&mut terminal, open::editor),
+                Action::Copy => self.copy_selection(),
+                Action::CopyLocation => self.copy_location(),
+                Action::Group => self.toggle_grouped(),
+                Action::Dates => self.dates.relative = !self.dates.relative,
+                Action::NextPalette => self.next_color(),
+                Action::PreviousPalette => self.previous_color(),
+                Action::Help => self.help = true,
+            }
+        }
+    }
+
+    /// Hands the terminal to the command `build` makes for the selected
+    /// file, i.e. the pager, and takes it back once the command exits.
+    fn open_external(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        build: fn(&Path) -> Option<Command>,
+    ) {
+        let Some(name) = self.preview_target() else {
+            return;
+        };
+        let path = match trash::files_dir() {
+            Ok(dir) => dir.join(&name),
+            Err(e) => return self.set_error(format!("cannot open '{}': {}", name, e)),
+        };
+        let Some(mut command) = build(&path) else {
+            return;
+        };
+
+        let _ = execute!(io::stdout(), DisableMouseCapture);
+        ratatui::restore();
+        let status = command.status();
+        let resumed = terminal::enable_raw_mode()
+            .and_then(|()| execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture))
+            .and_then(|()| terminal.clear());
+
+        let program = open::program(&command);
+        match (status, resumed) {
+            (_, Err(e)) => self.set_error(format!("cannot restore the terminal: {}", e)),
+            (Err(e), _) => self.set_error(format!("cannot run '{}': {}", program, e)),
+            (Ok(status), _) if !status.success() => {
+                self.set_error(format!("'{}' failed with {}", program, status));
+            }
+            (Ok(_), _) => {}
+        }
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if let Some(diff) = self.diff.as_mut() {
+            match mouse.kind {
+                MouseEventKind::ScrollDown => diff.scroll += 3,
+                MouseEventKind::ScrollUp => diff.scroll = diff.scroll.saturating_sub(3),
+                _ => {}
+            }
+            return;
+        }
+        if self.details.is_some() || self.help {
+            // A click closes the popup like any key
+            if let MouseEventKind::Down(_) = mouse.kind {
+                self.details = None;
+                self.help = false;
+            }
+            return;
+        }
+        if !self.pending_conflicts.is_empty()
+            || self.pending_action.is_some()
+            || self.path_input.is_some()
+        {
+            return;
+        }
+        if self.browser.is_some() {
+            self.handle_browser_mouse(mouse);
+            return;
+        }
+
+        let area = self.table_area;
+        let (x, y) = (mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::ScrollDown => {
+                self.select_row(self.state.selected().map_or(0, |i| i + 1));
+            }
+            MouseEventKind::ScrollUp => {
+                self.select_row(self.state.selected().unwrap_or(0).saturating_sub(1));
+            }
+            MouseEventKind::Down(MouseButton::Left) if self.on_scrollbar(x, y) => {
+                self.dragging_scrollbar = true;
+                self.jump_to(y);
+            }
+            MouseEventKind::Drag(MouseButton::Left) if self.dragging_scrollbar => self.jump_to(y),
+            MouseEventKind::Up(MouseButton::Left) => self.dragging_scrollbar = false,
+            MouseEventKind::Down(MouseButton::Left) if area.contains((x, y).into()) => {
+                let column = self
+                    .columns
+                    .iter()
+                    .position(|&(start, width)| (start..start + width).contains(&x));
+                if y == area.y {
+                    if let Some(column) = column {
+                        self.sort_by(SortColumn::ALL[column]);
+                    }
+                    return;
+                }
+
+                // The header takes one line and each row two.
+                let row = self.row_offset + usize::from(y - area.y - 1) / 2;
+                if row >= self.visible.len() {
+                    return;
+                }
+                if column.is_some() && column != self.state.selected_column() {
+                    self.state.select_column(column);
+                    self.h_scroll = 0;
+                }
+                if self.double_click(row) {
+                    self.show_details();
+                } else {
+                    self.select_row(row);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_browser_mouse(&mut self, mouse: MouseEvent) {
+        let area = self.table_area;
+        let Some(browser) = self.browser.as_mut() else {
+            return;
+        };
+        match mouse.kind {
+            MouseEventKind::ScrollDown
+                if browser
+                    .state
+                    .selected()
+                    .is_some_and(|i| i + 1 < browser.entries.len()) =>
+            {
+                browser.next_row();
+            }
+            MouseEventKind::ScrollUp if browser.state.selected().is_some_and(|i| i > 0) => {
+                browser.previous_row();
+            }
+            // Rows start below the top border.
+            MouseEventKind::Down(MouseButton::Left)
+                if area.contains((mouse.column, mouse.row).into()) && mouse.row > area.y =>
+            {
+                let row = browser.state.offset() + usize::from(mouse.row - area.y - 1);
+                if row >= browser.entries.len() {
+                    return;
+                }
+                if self.double_click(row) {
+                    if let Some(browser) = self.browser.as_mut() {
+                        browser.enter();
+                    }
+                } else if let Some(browser) = self.browser.as_mut() {
+                    browser.state.select(Some(row));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// True if `row` was clicked twice in quick succession. Remembers this
+    /// click for the next call otherwise.
+    fn double_click(&mut self, row: usize) -> bool {
+        let now = Instant::now();
+        let double = self
+            .last_click
+            .is_some_and(|(at, last)| last == row && now.duration_since(at) < DOUBLE_CLICK);
+        self.last_click = if double { None } else { Some((now, row)) };
+        double
+    }
+
+    /// True if (x, y) is on the scrollbar to the right of the table.
+    fn on_scrollbar(&self, x: u16, y: u16) -> bool {
+        let area = self.table_area;
+        area.width >= 2 && x == area.right() - 2 && y > area.y && y + 1 < area.bottom()
+    }
+
+    /// Selects the row at the same fraction of the list as `y` is of the
+    /// scrollbar.
+    fn jump_to(&mut self, y: u16) {
+        let top = self.table_area.y + 1;
+        let height = self.table_area.height.saturating_sub(3).max(1);
+        let fraction = f64::from(y.saturating_sub(top).min(height)) / f64::from(height);
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let row = (fraction * self.visible.len().saturating_sub(1) as f64).round() as usize;
+        self.select_row(row);
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let vertical = &Layout::vertical([
+            Constraint::Min(5),
+            Constraint::Length(1),
+            Constraint::Length(4),
+        ]);
+        let rects = vertical.split(frame.area());
+
+        self.set_colors();
+
+        let table_area = if self.show_preview {
+            let [table, preview] =
+                Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                    .areas(rects[0]);
+            self.render_preview(frame, preview);
+            table
+        } else {
+            rects[0]
+        };
+
+        self.table_area = table_area;
+        if self.browser.is_some() {
+            self.render_browser(frame, table_area);
+        } else if self.visible.is_empty() {
+            self.render_placeholder(frame, table_area);
+        } else {
+            self.render_table(frame, table_area);
+            self.render_scrollbar(frame, table_area);
+        }
+        self.render_status(frame, rects[1]);
+        self.render_footer(frame, rects[2]);
+
+        if !self.pending_conflicts.is_empty() {
+            self.render_conflict_popup(frame);
+        } else if self.details.is_some() {
+            self.render_details_popup(frame);
+        } else if self.diff.is_some() {
+            self.render_diff_popup(frame);
+        } else if self.help {
+            self.render_help_popup(frame);
+        } else if self.pending_action.is_some() {
+            self.render_action_popup(frame);
+        } else if self.path_input.is_some() {
+            self.render_path_popup(frame);
+        }
+    }
+
+    fn render_preview(&mut self, frame: &mut Frame, area: Rect) {
+        let name = self.preview_target();
+        let title = name.as_deref().map(|name| format!(" {} ", name));
+
+        // Only rebuild the preview when the selection or depth changes.
+        let stale = match (&self.preview, &name) {
+            (Some((cached, depth, _)), Some(name)) => {
+                cached != name || *depth != self.preview_depth
+            }
+            _ => true,
+        };
+        if stale {
+            self.preview = name.map(|name| {
+                let lines = match trash::files_dir() {
+                    Ok(dir) => preview::preview(&dir.join(&name), self.preview_depth),
+                    Err(e) => vec![format!("cannot read: {}", e)],
+                };
+                (name, self.preview_depth, lines)
+            });
+        }
+
+        let text = match &self.preview {
+            Some((_, _, lines)) => Text::from_iter(lines.iter().map(String::as_str)),
+            None => Text::from("nothing selected"),
+        };
+        let pane = Paragraph::new(text)
+            .style(
+                Style::new()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .block(
+                Block::bordered()
+                    .title(title.unwrap_or_default())
+                    .title_bottom(" (+/-) expand/collapse ")
+                    .border_type(BorderType::Double)
+                    .border_style(Style::new().fg(self.colors.footer_border_color)),
+            );
+        frame.render_widget(pane, area);
+    }
+
+    fn render_browser(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(browser) = &mut self.browser else {
+            return;
+        };
+        let rows = browser.entries.iter().enumerate().map(|(i, entry)| {
+            let color = match i % 2 {
+                0 => self.colors.normal_row_color,
+                _ => self.colors.alt_row_color,
+            };
+            let name = if entry.is_dir {
+                format!("{}/", entry.name)
+            } else {
+                entry.name.clone()
+            };
+            Row::new([name, entry.size_text.clone()])
+                .style(Style::new().fg(self.colors.row_fg).bg(color))
+        });
+        let table = Table::new(rows, [Constraint::Fill(1), Constraint::Length(10)])
+            .row_highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(self.colors.selected_row_style_fg),
+            )
+            .highlight_symbol(" █ ")
+            .highlight_spacing(HighlightSpacing::Always)
+            .bg(self.colors.buffer_bg)
+            .block(
+                Block::bordered()
+                    .title(format!(" {} ", browser.breadcrumbs()))
+                    .title_bottom(
+                        " (Enter) open | (Backspace) up | (r) restore | (Esc) back to trash ",
+                    )
+                    .border_type(BorderType::Double)
+                    .border_style(Style::new().fg(self.colors.footer_border_color)),
+            );
+        frame.render_stateful_widget(table, area, &mut browser.state);
+    }
+
+    fn render_path_popup(&self, frame: &mut Frame) {
+        let Some((_, input)) = &self.path_input else {
+            return;
+        };
+        let text = Text::from_iter([
+            format!("{}█", input),
+            String::new(),
+            "(Enter) restore | (Esc) cancel".to_string(),
+        ]);
+        let area = popup_area(frame.area(), 60, 5);
+        let popup = Paragraph::new(text)
+            .style(
+                Style::new()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .block(
+                Block::bordered()
+                    .title(" Restore to directory ")
+                    .border_type(BorderType::Double)
+                    .border_style(Style::new().fg(self.colors.footer_border_color)),
+            );
+        frame.render_widget(Clear, area);
+        frame.render_widget(popup, area);
+    }
+
+    fn render_details_popup(&self, frame: &mut Frame) {
+        let Some((name, lines)) = &self.details else {
+            return;
+        };
+        let text = Text::from_iter(lines.iter().map(String::as_str));
+        #[allow(clippy::cast_possible_truncation)]
+        let area = popup_area(frame.area(), 80, lines.len() as u16 + 2);
+        let popup = Paragraph::new(text)
+            .style(
+                Style::new()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::bordered()
+                    .title(format!(" {} ", name))
+                    .title_bottom(" press any key to close ")
+                    .border_type(BorderType::Double)
+                    .border_style(Style::new().fg(self.colors.footer_border_color)),
+            );
+        frame.render_widget(Clear, area);
+        frame.render_widget(popup, area);
+    }
+
+    fn render_diff_popup(&mut self, frame: &mut Frame) {
+        let Some(view) = self.diff.as_mut() else {
+            return;
+        };
+        let area = frame.area();
+        let area = popup_area(area, 90, area.height.saturating_sub(2));
+        // Keep the last page full rather than scrolling past the end.
+        view.page = usize::from(area.height.saturating_sub(2)).max(1);
+        view.scroll = view.scroll.min(view.lines.len().saturating_sub(view.page));
+
+        let colors = &self.colors;
+        let lines = view.lines.iter().map(|line| {
+            let style = match line.kind {
+                diff::Kind::Header => Style::new().add_modifier(Modifier::BOLD),
+                diff::Kind::Hunk => Style::new().fg(colors.hunk_fg),
+                diff::Kind::Added => Style::new().fg(colors.added_fg),
+                diff::Kind::Removed => Style::new().fg(colors.removed_fg),
+                diff::Kind::Context => Style::new(),
+                diff::Kind::Note => Style::new().add_modifier(Modifier::ITALIC),
+            };
+            Line::styled(line.text.replace('\t', "    "), style)
+        });
+        #[allow(clippy::cast_possible_truncation)]
+        let popup = Paragraph::new(Text::from_iter(lines))
+            .style(Style::new().fg(colors.row_fg).bg(colors.buffer_bg))
+            .scroll((view.scroll as u16, 0))
+            .block(
+                Block::bordered()
+                    .title(format!(" diff {} ", view.name))
+                    .title_bottom(" (↑↓) scroll | (PgUp/PgDn) page | (q) close ")
+                    .border_type(BorderType::Double)
+                    .border_style(Style::new().fg(colors.footer_border_color)),
+            );
+        frame.render_widget(Clear, area);
+        frame.render_widget(popup, area);
+
+        let mut scrollbar =
+            ScrollbarState::new(view.lines.len().saturating_sub(view.page)).position(view.scroll);
+        frame.render_stateful_widget(
+            Scrollbar::default()
+                .orientation(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None),
+            area.inner(Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut scrollbar,
+        );
+    }
+
+    fn render_action_popup(&self, frame: &mut Frame) {
+        let question = match &self.pending_action {
+            Some(BulkAction::Restore(names)) => format!(
+                "Restore {} item(s) ({})?",
+                names.len(),
+                trash::format_size(self.total_size(names))
+            ),
+            Some(BulkAction::Delete(names)) => format!(
+                "Permanently delete {} item(s) ({})?",
+                names.len(),
+                trash::format_size(self.total_size(names))
+            ),
+            Some(BulkAction::Empty) => format!(
+                "Permanently delete all {} item(s) in the trash ({})?",
+                self.items.len(),
+                trash::format_size(self.items.iter().map(|entry| entry.size).sum())
+            ),
+            None => return,
+        };
+        let text = Text::from_iter([question, String::new(), "(y) yes | (n) no".to_string()]);
+        let area = popup_area(frame.area(), 60, 5);
+        let popup = Paragraph::new(text)
+            .style(
+                Style::new()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .centered()
+            .block(
+                Block::bordered()
+                    .title(" Confirm ")
+                    .border_type(BorderType::Double)
+                    .border_style(Style::new().fg(self.colors.footer_border_color)),
+            );
+        frame.render_widget(Clear, area);
+        frame.render_widget(popup, area);
+    }
+
+    fn render_conflict_popup(&self, frame: &mut Frame) {
+        let Some((_, dest)) = self.pending_conflicts.front() else {
+            return;
+        };
+        let text = Text::from_iter([
+            format!("'{}' already exists.", dest.display()),
+            String::new(),
+            "(r) rename | (o) overwrite | (s) skip".to_string(),
+        ]);
+        let area = popup_area(frame.area(), 60, 5);
+        let popup = Paragraph::new(text)
+            .style(
+                Style::new()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .centered()
+            .block(
+                Block::bordered()
+                    .title(" Restore conflict ")
+                    .border_type(BorderType::Double)
+                    .border_style(Style::new().fg(self.colors.footer_border_color)),
+            );
+        frame.render_widget(Clear, area);
+        frame.render_widget(popup, area);
+    }
+
+    fn render_table(&mut self, frame: &mut Frame, area: Rect) {
+        let header_style = Style::default()
+            .fg(self.colors.header_fg)
+            .bg(self.colors.header_bg);
+        let selected_row_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(self.colors.selected_row_style_fg);
+        let selected_col_style = Style::default().fg(self.colors.selected_column_style_fg);
+        let selected_cell_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(self.colors.selected_cell_style_fg);
+
+        let bar = " █ ";
+        // Leave room for the highlight symbol and the scrollbar.
+        #[allow(clippy::cast_possible_truncation)]
+        let widths = self.column_widths(area.width.saturating_sub(bar.width() as u16 + 2));
+        // Remember where each column is drawn for the mouse. Hidden columns
+        // are kept with no width so the indexes match SortColumn::ALL.
+        #[allow(clippy::cast_possible_truncation)]
+        let mut x = area.x + bar.width() as u16;
+        self.columns = widths
+            .iter()
+            .map(|&width| {
+                let column = (x, width);
+                if width > 0 {
+                    x += width + 1;
+                }
+                column
+            })
+            .collect();
+        let shown: Vec<usize> = (0..widths.len()).filter(|&c| widths[c] > 0).collect();
+
+        let header = shown
+            .iter()
+            .map(|&c| {
+                let column = SortColumn::ALL[c];
+                if column != self.sort_column {
+                    Cell::from(column.title())
+                } else if self.sort_descending {
+                    Cell::from(format!("{} ▼", column.title()))
+                } else {
+                    Cell::from(format!("{} ▲", column.title()))
+                }
+            })
+            .collect::<Row>()
+            .style(header_style)
+            .height(1);
+        let match_style = Style::new()
+            .fg(self.colors.match_fg)
+            .add_modifier(Modifier::BOLD);
+        // Only build the rows that fit on screen: the header takes one line
+        // and each row two.
+        let page = usize::from(area.height.saturating_sub(1) / 2).max(1);
+        let selected = self.state.selected();
+        if let Some(selected) = selected {
+            // Scroll as little as possible to keep the selection on screen.
+            self.row_offset = self
+                .row_offset
+                .min(selected)
+                .max((selected + 1).saturating_sub(page));
+        }
+        self.row_offset = self.row_offset.min(self.visible.len().saturating_sub(page));
+        let window = self.row_offset..(self.row_offset + page).min(self.visible.len());
+
+        let now = Local::now().naive_local();
+        // The selected cell scrolls instead of losing its middle, but no
+        // further than its end.
+        let selected_column = self.state.selected_column().filter(|&c| widths[c] > 0);
+        if let (Some(item), Some(c)) = (self.selected_item(), selected_column) {
+            let text = self.cell_text(&self.items[item], c, now);
+            self.h_scroll = self.h_scroll.min(max_scroll(&text, widths[c]));
+        }
+
+        let marked: Vec<bool> = window.clone().map(|i| self.is_marked(i)).collect();
+        let rows = window.clone().map(|i| {
+            let row = &self.visible[i];
+            let color = match i % 2 {
+                0 => self.colors.normal_row_color,
+                _ => self.colors.alt_row_color,
+            };
+            let row_style = if marked[i - window.start] {
+                Style::new()
+                    .fg(self.colors.marked_fg)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::new().fg(self.colors.row_fg)
+            };
+            if let Some(group) = row.group.map(|g| &self.groups[g]) {
+                return self.group_header(group, &widths, row_style.bg(color), now);
+            }
+            let entry = &self.items[row.item];
+            // Old entries stand out as candidates for purging.
+            let row_style = match self.dates.age_color(entry.deleted_at, now, &self.colors) {
+                Some(fg) if !marked[i - window.start] => row_style.fg(fg),
+                _ => row_style,
+            };
+            shown
+                .iter()
+                .map(|&c| {
+                    let text = self.cell_text(entry, c, now);
+                    let hits: &[usize] = match c {
+                        0 => &row.file_hits,
+                        1 => &row.path_hits,
+                        _ => &[],
+                    };
+                    let scroll = (Some(i) == selected && Some(c) == selected_column)
+                        .then_some(self.h_scroll);
+                    let line = fit_line(&text, hits, widths[c], scroll, match_style);
+                    Cell::from(Text::from(vec![Line::default(), line, Line::default()]))
+                })
+                .collect::<Row>()
+                .style(row_style.bg(color))
+                .height(2)
+        });
+        let t = Table::new(rows, shown.iter().map(|&c| Constraint::Length(widths[c])))
+            .header(header)
+            .row_highlight_style(selected_row_style)
+            .column_highlight_style(selected_col_style)
+            .cell_highlight_style(selected_cell_style)
+            .highlight_symbol(Text::from(vec![
+                "".into(),
+                bar.into(),
+                bar.into(),
+                "".into(),
+            ]))
+            .bg(self.colors.buffer_bg)
+            .highlight_spacing(HighlightSpacing::Always);
+        let mut state = TableState::default()
+            .with_selected(selected.map(|i| i - window.start))
+            .with_selected_column(selected_column.and_then(|c| shown.iter().position(|&s| s == c)));
+        frame.render_stateful_widget(t, area, &mut state);
+    }
+
+    /// Shown in place of the table when there are no rows.
+    fn render_placeholder(&self, frame: &mut Frame, area: Rect) {
+        let lines = if self.items.is_empty() && self.loading.is_some() {
+            vec!["Loading the trash…".to_string()]
+        } else if self.items.is_empty() {
+            vec![
+                "The trash is empty".to_string(),
+                String::new(),
+                "Files deleted with rrm show up here.".to_string(),
+            ]
+        } else {
+            vec![
+                format!("No entries match '{}'", self.search),
+                String::new(),
+                "(Esc) clear search".to_string(),
+            ]
+        };
+        frame.render_widget(Block::new().bg(self.colors.buffer_bg), area);
+        #[allow(clippy::cast_possible_truncation)]
+        let [middle] = Layout::vertical([Constraint::Length(lines.len() as u16)])
+            .flex(Flex::Center)
+            .areas(area);
+        frame.render_widget(
+            Paragraph::new(Text::from_iter(lines))
+                .style(Style::new().fg(self.colors.row_fg))
+                .centered(),
+            middle,
+        );
+    }
+
+    /// Widths of the columns in SortColumn::ALL order when `width` columns
+    /// are available, 0 for hidden ones. The path takes whatever the others
+    /// leave and loses its middle when that is not enough.
+    fn column_widths(&self, width: u16) -> [u16; 4] {
+        let (file, path, size) = self.longest_item_lens;
+        // + 1 is for padding.
+        let file = (file + 1).min((width / 5 * 2).max(MIN_NAME_WIDTH));
+        let mut widths = [file, path + 1, self.dates.width() + 1, size];
+        // The size goes first as the terminal narrows, then the date.
+        for hide in [None, Some(3), Some(2)] {
+            if let Some(c) = hide {
+                widths[c] = 0;
+            }
+            #[allow(clippy::cast_possible_truncation)]
+            let spacing = widths.iter().filter(|&&w| w > 0).count() as u16 - 1;
+            let others = widths[0] + widths[2] + widths[3] + spacing;
+            let room = width.saturating_sub(others);
+            if room >= widths[1].min(MIN_PATH_WIDTH) {
+                widths[1] = room;
+                return widths;
+            }
+        }
+        // Only the name is left.
+        [width, 0, 0, 0]
+    }
+
+    /// Text of `entry` in the column at `column` of SortColumn::ALL.
+    fn cell_text<'a>(
+        &self,
+        entry: &'a TrashEntry,
+        column: usize,
+        now: NaiveDateTime,
+    ) -> Cow<'a, str> {
+        match SortColumn::ALL[column] {
+            SortColumn::Name => Cow::from(entry.file.as_str()),
+            SortColumn::Path => Cow::from(entry.path.as_str()),
+            SortColumn::Date => Cow::from(self.dates.text(entry.deleted_at, &entry.date, now)),
+            SortColumn::Size => Cow::from(entry.size_text.as_str()),
+        }
+    }
+
+    /// Row summarizing a group: its entry count, directory, latest deletion
+    /// and total size.
+    fn group_header(
+        &self,
+        group: &Group,
+        widths: &[u16; 4],
+        style: Style,
+        now: NaiveDateTime,
+    ) -> Row<'static> {
+        let arrow = if self.collapsed.contains(&group.dir) {
+            "▸"
+        } else {
+            "▾"
+        };
+        let latest = self.dates.text(group.latest, "", now);
+        [
+            format!("{} {} item(s)", arrow, group.names.len()),
+            format!("{}/", group.dir),
+            latest,
+            trash::format_size(group.size),
+        ]
+        .into_iter()
+        .zip(widths)
+        .filter(|&(_, &width)| width > 0)
+        .map(|(content, &width)| {
+            let line = fit_line(&content, &[], width, None, Style::new());
+            Cell::from(Text::from(vec![Line::default(), line]))
+        })
+        .collect::<Row>()
+        .style(style.add_modifier(Modifier::BOLD))
+        .height(2)
+    }
+
+    fn render_scrollbar(&mut self, frame: &mut Frame, area: Rect) {
+        frame.render_stateful_widget(
+            Scrollbar::default()
+                .orientation(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None),
+            area.inner(Margin {
+                vertical: 1,
+                horizontal: 1,
+            }),
+            &mut self.scroll_state,
+        );
+    }
+
+    /// Footer hints for `actions`, i.e. "(q) quit | (/) search", using the
+    /// first key bound to each. Unbound actions are left out.
+    fn footer_line(&self, actions: &[Action]) -> String {
+        actions
+            .iter()
+            .filter_map(|&action| {
+                let key = self.keymap.keys(action).first()?;
+                Some(format!("({}) {}", key, action.label()))
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+
+    fn render_help_popup(&self, frame: &mut Frame) {
+        let keys: Vec<String> = Action::ALL
+            .iter()
+            .map(|&action| {
+                self.keymap
+                    .keys(action)
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect();
+        let width = keys.iter().map(|keys| keys.width()).max().unwrap_or(0);
+        let mut lines: Vec<String> = Action::ALL
+            .iter()
+            .zip(&keys)
+            .filter(|(_, keys)| !keys.is_empty())
+            .map(|(action, keys)| {
+                let pad = " ".repeat(width - keys.width());
+                format!(" {}{}  {}", keys, pad, action.description())
+            })
+            .collect();
+        if let Some(path) = config::config_path() {
+            lines.push(String::new());
+            lines.push(format!(" Keys can be remapped in {}", path.display()));
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        let area = popup_area(frame.area(), 80, lines.len() as u16 + 2);
+        let popup = Paragraph::new(Text::from_iter(lines))
+            .style(
+                Style::new()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .block(
+                Block::bordered()
+                    .title(" Help ")
+                    .title_bottom(" press any key to close ")
+                    .border_type(BorderType::Double)
+                    .border_style(Style::new().fg(self.colors.footer_border_color)),
+            );
+        frame.render_widget(Clear, area);
+        frame.render_widget(popup, area);
+    }
+
+    /// Counts and sizes on the left, the result of the last action on the
+    /// right.
+    fn render_status(&self, frame: &mut Frame, area: Rect) {
+        let total: u64 = self.items.iter().map(|entry| entry.size).sum();
+        let mut summary = format!(
+            " {} item(s) | {} total",
+            self.items.len(),
+            trash::format_size(total)
+        );
+        if !self.marked.is_empty() {
+            summary.push_str(&format!(
+                " | {} marked ({})",
+                self.marked.len(),
+                trash::format_size(self.total_size(&self.marked))
+            ));
+        }
+
+        let style = Style::new()
+            .fg(self.colors.header_fg)
+            .bg(self.colors.header_bg);
+        let Some(status) = &self.status else {
+            frame.render_widget(Paragraph::new(summary).style(style), area);
+            return;
+        };
+        let message = format!(" {} ", status.text);
+        #[allow(clippy::cast_possible_truncation)]
+        let [left, right] = Layout::horizontal([
+            Constraint::Min(0),
+            Constraint::Length(message.width().min(usize::from(area.width)) as u16),
+        ])
+        .areas(area);
+        let message_style = if status.error {
+            Style::new()
+                .fg(self.colors.error_fg)
+                .bg(self.colors.error_bg)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            style.add_modifier(Modifier::BOLD)
+        };
+        frame.render_widget(Paragraph::new(summary).style(style), left);
+        frame.render_widget(Paragraph::new(message).style(message_style), right);
+    }
+
+    fn render_footer(&self, frame: &mut Frame, area: Rect) {
+        let text = if self.searching || !self.search.is_empty() {
+            let cursor = if self.searching { "█" } else { "" };
+            Text::from_iter([
+                format!(
+                    "/{}{}  ({} of {} entries)",
+                    self.search,
+                    cursor,
+                    self.visible.len(),
+                    self.items.len()
+                ),
+                [
+                    "(Enter) accept | (Esc) clear search".to_string(),
+                    self.footer_line(&[Action::NextMatch, Action::PreviousMatch]),
+                ]
+                .join(" | "),
+            ])
+        } else {
+            // Drop the hints that do not fit rather than cutting one in half.
+            let width = usize::from(area.width.saturating_sub(2));
+            Text::from_iter(FOOTER.map(|actions| {
+                let mut line = self.footer_line(actions);
+                while line.width() > width
+                    && let Some(end) = line.rfind(" | ")
+                {
+                    line.truncate(end);
+                }
+                line
+            }))
+        };
+        let title = if self.loading.is_some() {
+            format!(
+                " {} loading the trash… {} entries ",
+                SPINNER[self.spinner % SPINNER.len()],
+                self.items.len()
+            )
+        } else if self.visual_anchor.is_some() {
+            format!(
+                " -- VISUAL -- {} | (Esc) cancel ",
+                self.footer_line(&[Action::Visual])
+            )
+        } else {
+            String::new()
+        };
+        let info_footer = Paragraph::new(text)
+            .style(
+                Style::new()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .centered()
+            .block(
+                Block::bordered()
+                    .title(title)
+                    .border_type(BorderType::Double)
+                    .border_style(Style::new().fg(self.colors.footer_border_color)),
+            );
+        frame.render_widget(info_footer, area);
+    }
+}
+
+/// Reads the trash on a background thread, sending entries in batches so the
+/// table can fill in while the rest loads.
+fn load_trash_in_background() -> Receiver<Vec<TrashEntry>> {
+    let (sender, batches) = mpsc::channel();
+    thread::spawn(move || {
+        let Ok(dir_entries) = trash::files_dir().and_then(fs::read_dir) else {
+            return;
+        };
+        let names: Vec<String> = dir_entries
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+
+        for chunk in names.chunks(LOAD_BATCH) {
+            let batch = chunk.par_iter().cloned().map(TrashEntry::load).collect();
+            // The app quit or started a new load
+            if sender.send(batch).is_err() {
+                return;
+            }
+        }
+    });
+    batches
+}
+
+/// The wider of two sets of column widths.
+fn widest(a: (u16, u16, u16), b: (u16, u16, u16)) -> (u16, u16, u16) {
+    (a.0.max(b.0), a.1.max(b.1), a.2.max(b.2))
 }
 
 fn constraint_len_calculator(items: &[TrashEntry]) -> (u16, u16, u16) {
@@ -353,90 +2539,244 @@ fn constraint_len_calculator(items: &[TrashEntry]) -> (u16, u16, u16) {
         .map(UnicodeWidthStr::width)
         .max()
         .unwrap_or(0);
-    let date_len = items
+    // Leave room for the sort arrow in the header.
+    let size_len = items
         .iter()
-        .map(TrashEntry::date)
+        .map(TrashEntry::size_text)
         .map(UnicodeWidthStr::width)
+        .chain([SortColumn::Size.title().width() + 2])
         .max()
         .unwrap_or(0);
 
     #[allow(clippy::cast_possible_truncation)]
-    (name_len as u16, path_len as u16, date_len as u16)
+    (name_len as u16, path_len as u16, size_len as u16)
 }
 
-pub fn list_trash_contents_table() {
-    let home_dir = dirs_next::home_dir().expect("Failed to get home directory");
-    let trash_dir = home_dir.join(".local/share/Trash/files");
-
-    if !trash_dir.exists() {
-        println!("Trash directory does not exist at {:?}", trash_dir);
-        return;
+/// Shortens paths under the home directory to ~/...
+fn tilde(path: &Path) -> String {
+    match dirs_next::home_dir().and_then(|home| path.strip_prefix(home).ok().map(Path::to_owned)) {
+        Some(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+        Some(rest) => format!("~/{}", rest.display()),
+        None => path.display().to_string(),
     }
+}
 
-    let mut entries: Vec<TrashEntry> = Vec::new();
-
-    match fs::read_dir(&trash_dir) {
-        Ok(dir_entries) => {
-            let mut has_files = false;
-
-            for entry in dir_entries {
-                if let Ok(entry) = entry {
-                    let file_name = entry.file_name().to_string_lossy().to_string();
-                    let original_path = get_info_from_trashinfo(&file_name, "Path=")
-                        .unwrap_or_else(|| "Unknown".to_string());
-                    let date_info = get_info_from_trashinfo(&file_name, "DeletionDate=")
-                        .unwrap_or_else(|| "Unknown".to_string());
-
-                    entries.push(TrashEntry {
-                        file: file_name,
-                        path: original_path,
-                        date: date_info,
-                    });
-                    has_files = true;
-                }
-            }
+/// Fits `content` into `width` columns, styling the chars at `hits` with
+/// `style`. Text that is too long loses its middle, or with `scroll` shows
+/// the part starting at that char.
+fn fit_line(
+    content: &str,
+    hits: &[usize],
+    width: u16,
+    scroll: Option<usize>,
+    style: Style,
+) -> Line<'static> {
+    let width = usize::from(width);
+    if content.width() <= width {
+        return highlight_line(content, hits, style);
+    }
+    let chars: Vec<char> = content.chars().collect();
+    let kept = match scroll {
+        Some(start) => scroll_window(&chars, start, width),
+        None => middle_window(&chars, width),
+    };
+    // None stands for the ellipsis.
+    kept.into_iter()
+        .map(|i| match i {
+            Some(i) if hits.contains(&i) => Span::styled(chars[i].to_string(), style),
+            Some(i) => Span::raw(chars[i].to_string()),
+            None => Span::raw("…"),
+        })
+        .collect()
+}
 
-            if has_files {
-                match color_eyre::install() {
-                    Ok(it) => it,
-                    Err(_) => {}
-                };
+fn char_width(c: char) -> usize {
+    UnicodeWidthChar::width(c).unwrap_or(0)
+}
 
-                let terminal = ratatui::init();
-                let _ = App::new().run(terminal);
-                ratatui::restore();
-            } else {
-                println!("The trash is empty.");
-            }
+/// Indexes of the chars kept from both ends of `chars` around an ellipsis
+/// (None), i.e. /home/…/notes.txt
+fn middle_window(chars: &[char], width: usize) -> Vec<Option<usize>> {
+    let budget = width.saturating_sub(1);
+    let mut head = Vec::new();
+    let mut used = 0;
+    for (i, &c) in chars.iter().enumerate() {
+        if used + char_width(c) > budget.div_ceil(2) {
+            break;
+        }
+        used += char_width(c);
+        head.push(Some(i));
+    }
+    let mut tail = Vec::new();
+    for (i, &c) in chars.iter().enumerate().rev() {
+        if used + char_width(c) > budget || head.last() == Some(&Some(i)) {
+            break;
         }
+        used += char_width(c);
+        tail.push(Some(i));
+    }
+    head.push(None);
+    head.extend(tail.into_iter().rev());
+    head
+}
 
-        Err(e) => {
-            eprintln!("Failed to read the trash directory: {}", e);
+/// Indexes of the chars shown from `start` on, with an ellipsis (None) on
+/// each side that is cut off.
+fn scroll_window(chars: &[char], start: usize, width: usize) -> Vec<Option<usize>> {
+    let mut kept = Vec::new();
+    let mut used = 0;
+    if start > 0 {
+        kept.push(None);
+        used += 1;
+    }
+    let mut left: usize = chars.iter().skip(start).copied().map(char_width).sum();
+    for (i, &c) in chars.iter().enumerate().skip(start) {
+        if used + left <= width {
+            kept.extend((i..chars.len()).map(Some));
+            break;
         }
+        if used + char_width(c) + 1 > width {
+            kept.push(None);
+            break;
+        }
+        used += char_width(c);
+        left -= char_width(c);
+        kept.push(Some(i));
     }
+    kept
 }
 
-/// Extracts info from .trashinfo file. i.e. path or datetime.
-fn get_info_from_trashinfo(file_name: &str, search_term: &str) -> Option<String> {
-    let home_dir = dirs_next::home_dir()?;
-    let info_path = home_dir
-        .join(".local/share/Trash/info")
-        .join(format!("{}.trashinfo", file_name));
+/// Furthest `text` can be scrolled so that its end just fits in `width`
+/// columns after the leading ellipsis.
+fn max_scroll(text: &str, width: u16) -> usize {
+    let width = usize::from(width);
+    if text.width() <= width {
+        return 0;
+    }
+    let mut used = 1;
+    let mut start = text.chars().count();
+    for c in text.chars().rev() {
+        if used + char_width(c) > width {
+            break;
+        }
+        used += char_width(c);
+        start -= 1;
+    }
+    start
+}
 
-    if !info_path.exists() {
-        return None;
+/// Builds a line from `content`, styling the chars at `hits` with `style`.
+fn highlight_line(content: &str, hits: &[usize], style: Style) -> Line<'static> {
+    if hits.is_empty() {
+        return Line::from(content.to_string());
     }
+    content
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if hits.contains(&i) {
+                Span::styled(c.to_string(), style)
+            } else {
+                Span::raw(c.to_string())
+            }
+        })
+        .collect()
+}
 
-    let file = File::open(info_path).ok()?;
-    let reader = BufReader::new(file);
+/// Returns a centered area `percent_x` wide and `height` rows tall.
+fn popup_area(area: Rect, percent_x: u16, height: u16) -> Rect {
+    let vertical = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
 
-    for line in reader.lines() {
-        if let Ok(line) = line {
-            if line.starts_with(search_term) {
-                return Some(line[search_term.len()..].trim().to_string());
-            }
+pub fn list_trash_contents_table(on_conflict: ConflictPolicy) {
+    // An empty trash is still worth opening: the list fills in as soon as
+    // something is deleted, which needs both directories to watch.
+    for dir in [trash::files_dir(), trash::info_dir()] {
+        if let Err(e) = dir.and_then(fs::create_dir_all) {
+            eprintln!("rrm: cannot create the trash directory: {}", e);
+            return;
         }
     }
 
-    None
+    let _ = color_eyre::install();
+
+    let terminal = ratatui::init();
+    // ratatui restores the terminal on panic but does not know about mouse
+    // capture, so turn that off first.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = execute!(io::stdout(), DisableMouseCapture);
+        hook(info);
+    }));
+    let _ = execute!(io::stdout(), EnableMouseCapture);
+    let _ = App::new(on_conflict, config::load()).run(terminal);
+    let _ = execute!(io::stdout(), DisableMouseCapture);
+    ratatui::restore();
+}
+
+/// Lines of the details popup for the trash entry `name`.
+fn item_details(name: &str, policy: ConflictPolicy) -> Vec<String> {
+    let item = match trash::read_item(name) {
+        Ok(item) => item,
+        Err(e) => return vec![format!("cannot read trashinfo: {}", e)],
+    };
+    let path = item.file_path().unwrap_or_default();
+
+    let deleted = match item.deleted_at() {
+        Some(date) => format!(
+            "{} ({})",
+            date.format("%Y-%m-%d %H:%M:%S"),
+            trash::format_age(date, Local::now().naive_local())
+        ),
+        None => format!("{} (unparsable)", item.deletion_date),
+    };
+    let (mode, owner) = match fs::symlink_metadata(&path) {
+        Ok(meta) => (
+            format!(
+                "{:o} ({})",
+                meta.mode() & 0o7777,
+                preview::permissions(meta.mode())
+            ),
+            preview::owner(&meta),
+        ),
+        Err(e) => (format!("unknown ({})", e), "unknown".to_string()),
+    };
+    let parent = match item.original_path.parent() {
+        Some(parent) if parent.is_dir() => format!("{} (exists)", parent.display()),
+        Some(parent) => format!("{} (missing, will be created)", parent.display()),
+        None => "none".to_string(),
+    };
+    let conflict = if trash::path_exists(&item.original_path) {
+        format!(
+            "conflict, the original path exists (--on-conflict {})",
+            policy.name()
+        )
+    } else {
+        "no conflict".to_string()
+    };
+    let size = trash::disk_usage(&path);
+    let trash_dir = path
+        .parent()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_default();
+
+    vec![
+        format!("Original path: {}", item.original_path.display()),
+        format!("Deleted:       {}", deleted),
+        format!("Trash dir:     {}", trash_dir),
+        format!(
+            "Size:          {} ({} bytes, {} inodes)",
+            trash::format_size(size),
+            size,
+            trash::inode_count(&path)
+        ),
+        format!("Mode:          {}", mode),
+        format!("Owner:         {}", owner),
+        format!("Parent:        {}", parent),
+        format!("Restore:       {}", conflict),
+    ]
 }
@@ -0,0 +1,78 @@
+///
+/// =====================================================================
+/// Project Name: rust rm
+/// Description: An enhanced version of the common rm utility.
+/// Author: Robert Pellegrin
+/// Date: 2025-06-20
+/// Version: 0.0.1
+/// License: MIT
+/// Repository:
+/// =====================================================================
+///
+/// Notices when another program trashes or restores files, so the TUI can
+/// refresh itself. Uses inotify where available and polls otherwise.
+///
+use notify::{Config, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+use std::{
+    sync::mpsc::{self, Receiver},
+    time::Duration,
+};
+
+use crate::trash;
+
+/// How often the polling fallback rescans the trash.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Watches the files/ and info/ directories of the trash.
+pub struct TrashWatcher {
+    // Dropping the watcher stops it, so keep it alive alongside the channel.
+    _watcher: Box<dyn Watcher>,
+    events: Receiver<()>,
+}
+
+impl TrashWatcher {
+    /// Starts watching. Returns None if neither inotify nor polling works,
+    /// i.e. the trash directories do not exist.
+    pub fn new() -> Option<Self> {
+        let (sender, events) = mpsc::channel();
+        let handler = move |event: notify::Result<notify::Event>| {
+            // Reading the trash opens files too, which must not count as a
+            // change or every refresh would trigger the next one.
+            if event.is_ok_and(|event| !matches!(event.kind, EventKind::Access(_))) {
+                let _ = sender.send(());
+            }
+        };
+
+        let watcher: Box<dyn Watcher> =
+            match RecommendedWatcher::new(handler.clone(), Config::default()).and_then(watch_trash)
+            {
+                Ok(watcher) => Box::new(watcher),
+                Err(_) => {
+                    let config = Config::default().with_poll_interval(POLL_INTERVAL);
+                    Box::new(
+                        PollWatcher::new(handler, config)
+                            .and_then(watch_trash)
+                            .ok()?,
+                    )
+                }
+            };
+
+        Some(Self {
+            _watcher: watcher,
+            events,
+        })
+    }
+
+    /// True if the trash changed since the last call.
+    pub fn changed(&self) -> bool {
+        // Drain everything so a burst of events causes a single refresh.
+        self.events.try_iter().count() > 0
+    }
+}
+
+fn watch_trash<W: Watcher>(mut watcher: W) -> notify::Result<W> {
+    for dir in [trash::files_dir(), trash::info_dir()] {
+        watcher.watch(&dir?, RecursiveMode::NonRecursive)?;
+    }
+    Ok(watcher)
+}